@@ -4,8 +4,7 @@ pub fn part1(input: &str) -> Answer {
     let (mut left, mut right): (Vec<_>, Vec<_>) = input
         .lines()
         .map(|line| {
-            let (left, line) = parse::<i64>(line);
-            let (right, _) = parse::<i64>(line.trim_ascii_start());
+            let [left, right] = parse_n::<i64, 2>(line).unwrap();
             (left, right)
         })
         .unzip();
@@ -25,7 +24,7 @@ pub fn part2(input: &str) -> Answer {
         parse(line).0
     });
     let counter: Counter<i64> = right.collect();
-    left.map(|n| n * (counter.get(&n).copied().unwrap_or(0) as i64))
+    left.map(|n| n * (counter.get(&n) as i64))
         .sum::<i64>()
         .into()
 }
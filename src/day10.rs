@@ -12,32 +12,25 @@ const _SAMPLE: &str = "\
 10456732";
 
 pub fn part1(input: &str) -> Answer {
-    type TrailMap = HashMap<Index, HashSet<usize>>;
+    let grid = &Grid::new(input);
 
-    let grid = Grid::new(input);
-    let mut trails: TrailMap = grid
-        .iter()
+    grid.iter()
         .filter(|&(_, tile)| tile == b'0')
-        .enumerate()
-        .map(|(trailhead, (index, _))| (index, HashSet::from_iter([trailhead])))
-        .collect();
-    let mut new_trails = TrailMap::default();
-
-    for step in '1'..='9' {
-        for (index, trailheads) in trails.drain() {
-            for new_index in dirs(index) {
-                if grid.get(new_index).map(char::from) == Some(step) {
-                    new_trails
-                        .entry(new_index)
-                        .or_default()
-                        .extend(trailheads.iter().copied());
-                }
-            }
-        }
-        mem::swap(&mut trails, &mut new_trails);
-    }
-
-    trails.values().flatten().count().into()
+        .map(|(trailhead, _)| {
+            let uphill = |&index: &Index| {
+                let next = Byte(grid.get(index).unwrap().0 + 1);
+                dirs(index)
+                    .into_iter()
+                    .filter(move |&neighbor| grid.get(neighbor) == Some(next))
+            };
+            search::bfs(trailhead, uphill, |_| false)
+                .dist
+                .keys()
+                .filter(|&&index| grid.get(index).is_some_and(|b| b == '9'))
+                .count()
+        })
+        .sum::<usize>()
+        .into()
 }
 
 pub fn part2(input: &str) -> Answer {
@@ -1,5 +1,5 @@
 use crate::utils::*;
-use std::array;
+use std::str::Lines;
 
 const _SAMPLE: &str = "\
 Button A: X+94, Y+34
@@ -18,27 +18,82 @@ Button A: X+69, Y+23
 Button B: X+27, Y+71
 Prize: X=18641, Y=10279";
 
-fn parse_line(line: &str) -> (i64, i64) {
-    let (_, x_and_y) = line.split_once(": ").unwrap();
-    let (x, y) = x_and_y.split_once(", ").unwrap();
-    let (x, _) = parse(&x[2..]);
-    let (y, _) = parse(&y[2..]);
-    (x, y)
+fn parse_claw(lines: &mut Lines) -> [(i64, i64); 3] {
+    let mut next = || lines.next().unwrap();
+    [
+        scan!("Button A: X+{}, Y+{}", next()).unwrap(),
+        scan!("Button B: X+{}, Y+{}", next()).unwrap(),
+        scan!("Prize: X={}, Y={}", next()).unwrap(),
+    ]
 }
 
-fn calc_claw([(a_x, a_y), (b_x, b_y), (prize_x, prize_y)]: [(i64, i64); 3]) -> Option<i64> {
-    let (numer, denom) = (a_x * prize_y - a_y * prize_x, a_x * b_y - a_y * b_x);
-    (numer % denom == 0).then(|| numer / denom).and_then(|b| {
-        let (numer, denom) = (-b_x * b + prize_x, a_x);
-        (numer % denom == 0).then(|| 3 * numer / denom + b)
-    })
+const A_COST: i64 = 3;
+const B_COST: i64 = 1;
+
+fn calc_claw([a, b, prize]: [(i64, i64); 3]) -> Option<i64> {
+    let matrix = [vec![a.0.into(), b.0.into()], vec![a.1.into(), b.1.into()]];
+    match linear::solve(&matrix, &[prize.0.into(), prize.1.into()]) {
+        linear::Solution::Unique(presses) => {
+            let [a, b] = [presses[0], presses[1]].map(Rational::to_integer);
+            let (a, b) = (i64::try_from(a?).ok()?, i64::try_from(b?).ok()?);
+            (a >= 0 && b >= 0).then_some(A_COST * a + B_COST * b)
+        }
+        linear::Solution::None => None,
+        // the buttons are collinear, so either equation alone describes every solution
+        linear::Solution::Infinite { .. } if (a.0, b.0) != (0, 0) => cheapest(a.0, b.0, prize.0),
+        linear::Solution::Infinite { .. } => cheapest(a.1, b.1, prize.1),
+    }
+}
+
+// the cheapest non-negative integer `(a, b)` with `a * a_step + b * b_step == target`
+fn cheapest(a_step: i64, b_step: i64, target: i64) -> Option<i64> {
+    if (a_step, b_step) == (0, 0) {
+        return (target == 0).then_some(0);
+    }
+    let (gcd, x, y) = extended_gcd(a_step, b_step);
+    if target % gcd != 0 {
+        return None;
+    }
+    // every solution is `(a0 + a_dir * t, b0 - b_dir * t)`
+    let (a0, b0) = (x * (target / gcd), y * (target / gcd));
+    let (a_dir, b_dir) = (b_step / gcd, a_step / gcd);
+    let (mut lowest, mut highest) = (None::<i64>, None::<i64>);
+    let mut bound = |dir: i64, start: i64| {
+        if dir != 0 {
+            let t = Rational::new((-start).into(), dir.into());
+            if dir > 0 {
+                lowest = lowest.max(Some(t.ceil() as _));
+            } else {
+                highest = Some(highest.map_or(t.floor() as _, |h| h.min(t.floor() as _)));
+            }
+        }
+    };
+    bound(a_dir, a0);
+    bound(-b_dir, b0);
+    if a_dir == 0 && a0 < 0 || b_dir == 0 && b0 < 0 {
+        return None;
+    }
+    if let (Some(lowest), Some(highest)) = (lowest, highest) {
+        if lowest > highest {
+            return None;
+        }
+    }
+
+    let slope = A_COST * a_dir - B_COST * b_dir;
+    let t = if slope > 0 {
+        lowest.or(highest)
+    } else {
+        highest.or(lowest)
+    }
+    .unwrap_or(0);
+    Some(A_COST * (a0 + a_dir * t) + B_COST * (b0 - b_dir * t))
 }
 
 pub fn part1(input: &str) -> Answer {
     let mut lines = input.lines();
     let mut tokens = 0;
     loop {
-        let claw = array::from_fn(|_| parse_line(lines.next().unwrap()));
+        let claw = parse_claw(&mut lines);
         tokens += calc_claw(claw).unwrap_or(0);
         if lines.next().is_none() {
             break;
@@ -53,7 +108,7 @@ pub fn part2(input: &str) -> Answer {
     let mut lines = input.lines();
     let mut tokens = 0;
     loop {
-        let mut claw = array::from_fn(|_| parse_line(lines.next().unwrap()));
+        let mut claw = parse_claw(&mut lines);
         claw[2].0 += CORRECTION;
         claw[2].1 += CORRECTION;
         tokens += calc_claw(claw).unwrap_or(0);
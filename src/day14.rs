@@ -1,12 +1,13 @@
 use crate::utils::*;
 
-const WIDTH: i64 = 101;
-const LEFT: i64 = WIDTH / 2;
-const RIGHT: i64 = LEFT + 1;
-const HEIGHT: i64 = 103;
-const TOP: i64 = HEIGHT / 2;
-const BOTTOM: i64 = TOP + 1;
-const SECONDS: i64 = 100;
+const WIDTH: isize = 101;
+const LEFT: isize = WIDTH / 2;
+const RIGHT: isize = LEFT + 1;
+const HEIGHT: isize = 103;
+const TOP: isize = HEIGHT / 2;
+const BOTTOM: isize = TOP + 1;
+const BOUNDS: Index = Point::from_xy(WIDTH, HEIGHT);
+const SECONDS: isize = 100;
 
 const _SAMPLE: &str = "\
 p=0,4 v=3,-3
@@ -24,23 +25,15 @@ p=9,5 v=-3,-3";
 
 #[derive(Hash, PartialEq, Eq, Copy, Clone, Debug)]
 struct Robot {
-    pos: (i64, i64),
-    vel: (i64, i64),
+    pos: Index,
+    vel: Index,
 }
 
 fn parse_robot(line: &str) -> Robot {
-    let (pos, vel) = line.split_once(' ').unwrap();
-    let pos = &pos[2..];
-    let (pos_x, pos) = parse(pos);
-    let pos = &pos[1..];
-    let (pos_y, _) = parse(pos);
-    let vel = &vel[2..];
-    let (vel_x, vel) = parse(vel);
-    let vel = &vel[1..];
-    let (vel_y, _) = parse(vel);
+    let (pos_x, pos_y, vel_x, vel_y) = scan!("p={},{} v={},{}", line).unwrap();
     Robot {
-        pos: (pos_x, pos_y),
-        vel: (vel_x, vel_y),
+        pos: Point::from_xy(pos_x, pos_y),
+        vel: Point::from_xy(vel_x, vel_y),
     }
 }
 
@@ -49,9 +42,8 @@ pub fn part1(input: &str) -> Answer {
     input
         .lines()
         .map(parse_robot)
-        .map(|Robot { pos, vel }| (vel.0 * SECONDS + pos.0, vel.1 * SECONDS + pos.1))
-        .map(|(x, y)| (x.rem_euclid(WIDTH), y.rem_euclid(HEIGHT)))
-        .flat_map(|pos| match pos {
+        .map(|Robot { pos, vel }| wrapping_add(pos, vel * SECONDS, BOUNDS).unwrap())
+        .flat_map(|pos| match pos.to_xy() {
             (..LEFT, ..TOP) => Some(0),
             (RIGHT.., ..TOP) => Some(1),
             (..LEFT, BOTTOM..) => Some(2),
@@ -64,40 +56,63 @@ pub fn part1(input: &str) -> Answer {
         .into()
 }
 
-pub fn part2(_: &str) -> Answer {
-    Answer::Number(6771)
+// the tree shows up when the robots cluster on both axes at once, and since each axis repeats
+// with its own period, the two best times can be combined with the chinese remainder theorem
+pub fn part2(input: &str) -> Answer {
+    let robots = input.lines().map(parse_robot).collect_vec();
+    let spread = |period, axis: fn(Index) -> isize| {
+        (0..period)
+            .min_by_key(|&second| {
+                let positions = robots
+                    .iter()
+                    .map(|&Robot { pos, vel }| {
+                        axis(wrapping_add(pos, vel * second, BOUNDS).unwrap())
+                    })
+                    .collect_vec();
+                let mean = positions.iter().sum::<isize>() / positions.len() as isize;
+                positions
+                    .iter()
+                    .map(|pos| (pos - mean).pow(2))
+                    .sum::<isize>()
+            })
+            .unwrap()
+    };
+    let (x_second, y_second) = (spread(WIDTH, Point::x), spread(HEIGHT, Point::y));
+    crt([(x_second, WIDTH), (y_second, HEIGHT)])
+        .unwrap()
+        .0
+        .into()
 }
 
 fn _part2_impl(input: &str) -> Answer {
     let mut robots = input.lines().map(parse_robot).collect_vec();
-    let line = " ".repeat(WIDTH as _) + "\n";
-    let grid = GridOwned::new(&line.repeat(HEIGHT as _));
+    let mut room = TorusGrid::new_dims(BOUNDS);
     for _ in 0..76 {
         for Robot { pos, vel } in &mut robots {
-            pos.0 += vel.0;
-            pos.1 += vel.1;
-            pos.0 = pos.0.rem_euclid(WIDTH);
-            pos.1 = pos.1.rem_euclid(HEIGHT);
+            *pos = room.add(*pos, *vel).unwrap();
         }
     }
     for second in 0.. {
         if second % 103 == 0 {
+            for (_, tile) in room.iter_mut() {
+                tile.make_null();
+            }
+            for &Robot { pos, .. } in &robots {
+                room.set(pos, '#');
+            }
             println!();
-            grid.print_with(|(row, column)| {
-                robots
-                    .iter()
-                    .any(|&Robot { pos: (x, y), .. }| x == column as i64 && y == row as i64)
-                    .then_some('⬜')
-                    .or(Some('⬛'))
+            room.print_with(|index| {
+                Some(if room.get(index).is_some_and(|b| b == '#') {
+                    '⬜'
+                } else {
+                    '⬛'
+                })
             });
             println!("Second: {}", second + 76);
             sleep(300);
         }
         for Robot { pos, vel } in &mut robots {
-            pos.0 += vel.0;
-            pos.1 += vel.1;
-            pos.0 = pos.0.rem_euclid(WIDTH);
-            pos.1 = pos.1.rem_euclid(HEIGHT);
+            *pos = room.add(*pos, *vel).unwrap();
         }
     }
     Answer::Unfinished
@@ -1,5 +1,5 @@
 use crate::utils::*;
-use std::{hint, mem};
+use std::hint;
 
 const _SAMPLE: &str = "\
 ##########
@@ -34,9 +34,9 @@ pub fn part1(input: &str) -> Answer {
     map.set(robot, '.');
 
     let dirs = moves
-        .lines()
-        .flat_map(str::bytes)
-        .map(|b| unsafe { mem::transmute(b) });
+        .bytes()
+        .filter(|b| !b.is_ascii_whitespace())
+        .map(|b| Dir::try_from(b).unwrap());
 
     for dir in dirs {
         match unsafe { map.get(robot + dir).unwrap_unchecked() }.into() {
@@ -67,7 +67,7 @@ pub fn part1(input: &str) -> Answer {
     }
 
     map.iter()
-        .flat_map(|((i, j), b)| (b == 'O').then(|| 100 * i + j))
+        .flat_map(|(Point { row, column }, b)| (b == 'O').then(|| 100 * row + column))
         .sum::<isize>()
         .into()
 }
@@ -123,9 +123,9 @@ pub fn part2(input: &str) -> Answer {
     map.set(robot, '.');
 
     let dirs = moves
-        .lines()
-        .flat_map(str::bytes)
-        .map(|b| unsafe { mem::transmute(b) });
+        .bytes()
+        .filter(|b| !b.is_ascii_whitespace())
+        .map(|b| Dir::try_from(b).unwrap());
 
     let mut cached = HashSet::default();
     for dir in dirs {
@@ -153,7 +153,7 @@ pub fn part2(input: &str) -> Answer {
     }
 
     map.iter()
-        .flat_map(|((i, j), b)| (b == '[').then(|| 100 * i + j))
+        .flat_map(|(Point { row, column }, b)| (b == '[').then(|| 100 * row + column))
         .sum::<isize>()
         .into()
 }
@@ -1,5 +1,15 @@
 use crate::utils::*;
-use std::{array, fmt::Debug, hint};
+use decompile::{decompile, DecompileError, Expr};
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+};
+use vm::{Program, Registers};
+
+pub mod asm;
+pub mod decompile;
+pub mod trace;
+pub mod vm;
 
 const _SAMPLE: &str = "\
 Register A: 202367025818154
@@ -8,154 +18,100 @@ Register C: 0
 
 Program: 2,4,1,1,7,5,4,7,1,4,0,3,5,5,3,0";
 
+pub fn parse_debugger(input: &str) -> (Registers, Program) {
+    let mut lines = input.lines();
+    let registers = [
+        scan!("Register A: {}", lines.next().unwrap()).unwrap(),
+        scan!("Register B: {}", lines.next().unwrap()).unwrap(),
+        scan!("Register C: {}", lines.next().unwrap()).unwrap(),
+    ];
+    lines.next().unwrap();
+    let program: &str = scan!("Program: {}", lines.next().unwrap()).unwrap();
+    (registers, Program::parse(program).unwrap())
+}
+
 pub fn part1(input: &str) -> Answer {
-    type Registers = [u64; 3];
+    let (registers, program) = parse_debugger(input);
+    program
+        .machine(registers)
+        .run()
+        .unwrap()
+        .iter()
+        .join(",")
+        .into()
+}
 
-    #[derive(Copy, Clone)]
-    struct Op(u64, u64);
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QuineError {
+    Shape(DecompileError),
+    ShiftNotThree,
+    OutputCount(usize),
+    // the registers carried over between loops
+    CarriedState(Vec<char>),
+    NotFound,
+}
 
-    impl Debug for Op {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            match self.0 {
-                0 => write!(f, "ADV({})", self.1),
-                1 => write!(f, "BXL({})", self.1),
-                2 => write!(f, "BST({})", self.1),
-                3 => write!(f, "JNZ({})", self.1),
-                4 => write!(f, "BXC"),
-                5 => write!(f, "OUT({})", self.1),
-                6 => write!(f, "BDV({})", self.1),
-                7 => write!(f, "CDV({})", self.1),
-                _ => unreachable!(),
+impl Display for QuineError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Shape(err) => write!(f, "{err}"),
+            Self::ShiftNotThree => write!(f, "program doesn't shift A right by 3 each loop"),
+            Self::OutputCount(count) => {
+                write!(f, "program outputs {count} values per loop instead of one")
             }
+            Self::CarriedState(registers) => write!(
+                f,
+                "program's output depends on {} from the previous loop",
+                registers.iter().join(" and ")
+            ),
+            Self::NotFound => write!(f, "no value of A makes the program output itself"),
         }
     }
+}
 
-    fn parse_debugger(input: &str) -> (Registers, Vec<Op>) {
-        let mut lines = input.lines();
-        let registers = array::from_fn(|_| parse(&lines.next().unwrap()[12..]).0);
-        lines.next().unwrap();
-        let program = &lines.next().unwrap()[9..];
-        let mut octals = program
-            .split(',')
-            .map(|s| s.as_bytes()[0] - b'0')
-            .map_into();
-        let mut ops = vec![];
-        while let Some(opcode) = octals.next() {
-            ops.push(Op(opcode, octals.next().unwrap()));
-        }
-        (registers, ops)
-    }
+impl Error for QuineError {}
 
-    fn get_combo(combo: u64, [a, b, c]: Registers) -> u64 {
-        match combo {
-            0..=3 => combo,
-            4 => a,
-            5 => b,
-            6 => c,
-            7.. => unsafe { hint::unreachable_unchecked() },
-        }
+// the search relies on each loop iteration outputting one digit from A alone and then
+// dropping the lowest octal digit of A, so the last output only depends on the highest digit
+fn find_quine(program: &Program, [_, b, c]: Registers) -> Result<u64, QuineError> {
+    // builds A from its highest octal digit down, matching one more output from the end each time
+    fn find_a(a: u64, unmatched: &[u64], output: &dyn Fn(u64) -> u64) -> Option<u64> {
+        let Some((&last, unmatched)) = unmatched.split_last() else {
+            return Some(a);
+        };
+        (0..8).map(|digit| a * 8 + digit).find_map(|a| {
+            (a != 0 && output(a) == last)
+                .then(|| find_a(a, unmatched, output))
+                .flatten()
+        })
     }
 
-    enum Out {
-        Output(u64),
-        Jump(usize),
-        None,
+    let decompiled = decompile(program).map_err(QuineError::Shape)?;
+    if decompiled.registers[0] != Expr::A >> Expr::Literal(3) {
+        return Err(QuineError::ShiftNotThree);
     }
-
-    fn apply(Op(opcode, operand): Op, registers: &mut Registers) -> Out {
-        match opcode {
-            0 => {
-                let combo = get_combo(operand, *registers);
-                let [a, ..] = registers;
-                *a = *a / 2u64.pow(combo.try_into().unwrap());
-                Out::None
-            }
-            1 => {
-                let [_, b, ..] = registers;
-                *b ^= operand;
-                Out::None
-            }
-            2 => {
-                let combo = get_combo(operand, *registers);
-                let [_, b, ..] = registers;
-                *b = combo % 8;
-                Out::None
-            }
-            3 => {
-                let &mut [a, ..] = registers;
-                if a != 0 {
-                    Out::Jump(operand as usize / 2)
-                } else {
-                    Out::None
-                }
-            }
-            4 => {
-                let [.., b, c] = registers;
-                *b ^= *c;
-                Out::None
-            }
-            5 => {
-                let combo = get_combo(operand, *registers);
-                Out::Output(combo % 8)
-            }
-            6 => {
-                let combo = get_combo(operand, *registers);
-                let [a, b, ..] = registers;
-                *b = *a / 2u64.pow(combo.try_into().unwrap());
-                Out::None
-            }
-            7 => {
-                let combo = get_combo(operand, *registers);
-                let [a, .., c] = registers;
-                *c = *a / 2u64.pow(combo.try_into().unwrap());
-                Out::None
-            }
-            _ => unsafe { hint::unreachable_unchecked() },
-        }
+    let [output] = &decompiled.outputs[..] else {
+        return Err(QuineError::OutputCount(decompiled.outputs.len()));
+    };
+    // B or C left over from the lower digits' iterations would change the output, so A's
+    // higher digits couldn't be fixed first
+    if output.reads_state() {
+        let carried = [('B', Expr::B), ('C', Expr::C)]
+            .into_iter()
+            .filter(|(_, register)| output.reads(register))
+            .map(|(name, _)| name)
+            .collect();
+        return Err(QuineError::CarriedState(carried));
     }
-
-    let (mut registers, ops) = parse_debugger(input);
-    let mut pc = 0;
-    let mut outputs = vec![];
-    while pc < ops.len() {
-        match apply(ops[pc], &mut registers) {
-            Out::Output(output) => {
-                outputs.push(output);
-                pc += 1;
-            }
-            Out::Jump(jump) => pc = jump,
-            Out::None => pc += 1,
-        }
-    }
-    outputs.iter().join(",").into()
+    let octals = program.octals().collect_vec();
+    find_a(0, &octals, &|a| output.eval([a, b, c])).ok_or(QuineError::NotFound)
 }
 
 pub fn part2(input: &str) -> Answer {
-    fn find_a(a: u64, expected: &[u64]) -> Option<u64> {
-        if let [expect_b, expected @ ..] = expected {
-            (0..8).map(|i| a + i).find_map(|a| {
-                let b = (a % 8) ^ 1;
-                let c = a >> b;
-                if (b ^ c ^ 4) % 8 == *expect_b {
-                    find_a(a * 8, expected)
-                } else {
-                    None
-                }
-            })
-        } else {
-            Some(a)
-        }
-    }
-
-    let (_, program) = input.split_once("\n\n").unwrap();
-    let program = &program[9..];
-    let outputs = program
-        .split(',')
-        .rev()
-        .map(|output| output.parse().unwrap())
-        .collect_vec();
-
-    (find_a(0, &outputs).unwrap() / 8).into()
+    let (registers, program) = parse_debugger(input);
+    find_quine(&program, registers)
+        .unwrap_or_else(|err| panic!("{err}"))
+        .into()
 }
 
 // pub fn part2_attempt1(input: &str) -> Answer {
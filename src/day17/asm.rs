@@ -0,0 +1,147 @@
+use super::vm::{Op, Program, VmError};
+use crate::utils::*;
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter, Write},
+};
+
+const MNEMONICS: [&str; 8] = ["adv", "bxl", "bst", "jnz", "bxc", "out", "bdv", "cdv"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AsmError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl Display for AsmError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for AsmError {}
+
+fn combo_name(operand: u64) -> String {
+    match operand {
+        4 => "a".into(),
+        5 => "b".into(),
+        6 => "c".into(),
+        _ => operand.to_string(),
+    }
+}
+
+fn label(target: u64) -> String {
+    format!("l{target}")
+}
+
+// one instruction per line, as `mnemonic [operand]`, with `label:` lines marking jump targets
+// and `;` starting a comment. combo operands are `a`, `b`, `c` or a literal from 0 to 3,
+// and `jnz` takes either a label or a literal
+pub fn assemble(source: &str) -> Result<Program, AsmError> {
+    let lines = source
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.split(';').next().unwrap().trim()))
+        .filter(|(_, line)| !line.is_empty())
+        .collect_vec();
+
+    let mut labels = HashMap::default();
+    let mut pc = 0u64;
+    for &(line, text) in &lines {
+        if let Some(name) = text.strip_suffix(':') {
+            if labels.insert(name.trim(), pc * 2).is_some() {
+                let message = format!("duplicate label {name:?}");
+                return Err(AsmError { line, message });
+            }
+        } else {
+            pc += 1;
+        }
+    }
+
+    let mut ops = vec![];
+    for &(line, text) in lines.iter().filter(|(_, text)| !text.ends_with(':')) {
+        let error = |message: String| AsmError { line, message };
+        let (mnemonic, operand) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+        let operand = operand.trim();
+        let opcode = MNEMONICS
+            .iter()
+            .position(|m| m.eq_ignore_ascii_case(mnemonic))
+            .ok_or_else(|| error(format!("unknown mnemonic {mnemonic:?}")))?
+            as u64;
+        let literal = |operand: &str| {
+            try_parse::<u64>(operand)
+                .filter(|&(n, rest)| rest.is_empty() && n < 8)
+                .map(|(n, _)| n)
+        };
+        let operand = match opcode {
+            Op::BXC if operand.is_empty() => 0,
+            Op::JNZ => literal(operand)
+                .or_else(|| labels.get(operand).copied())
+                .ok_or_else(|| error(format!("unknown jump target {operand:?}")))?,
+            _ if Op(opcode, 0).takes_combo() => match operand.to_ascii_lowercase().as_str() {
+                "a" => 4,
+                "b" => 5,
+                "c" => 6,
+                _ => literal(operand)
+                    .filter(|&n| n < 4)
+                    .ok_or_else(|| error(format!("invalid combo operand {operand:?}")))?,
+            },
+            _ => literal(operand)
+                .ok_or_else(|| error(format!("invalid literal operand {operand:?}")))?,
+        };
+        ops.push(Op(opcode, operand));
+    }
+
+    Program::new(ops).map_err(|err| {
+        let pc = match err {
+            VmError::InvalidOctal { pc, .. }
+            | VmError::InvalidCombo { pc, .. }
+            | VmError::OddJump { pc, .. } => pc,
+            _ => 0,
+        };
+        let line = lines
+            .iter()
+            .filter(|(_, text)| !text.ends_with(':'))
+            .nth(pc)
+            .map_or(0, |&(line, _)| line);
+        AsmError {
+            line,
+            message: err.to_string(),
+        }
+    })
+}
+
+// the inverse of `assemble`, with each instruction's pointer and octals in a comment
+pub fn disassemble(program: &Program) -> String {
+    let end = program.len() as u64 * 2;
+    let targets: HashSet<_> = program
+        .iter()
+        .filter(|&&Op(opcode, _)| opcode == Op::JNZ)
+        .map(|&Op(_, target)| target)
+        .filter(|&target| target <= end)
+        .collect();
+
+    let mut source = String::new();
+    for (pc, &op @ Op(opcode, operand)) in program.iter().enumerate() {
+        let pointer = pc as u64 * 2;
+        if targets.contains(&pointer) {
+            writeln!(source, "{}:", label(pointer)).unwrap();
+        }
+        let mnemonic = MNEMONICS[opcode as usize];
+        let instruction = match opcode {
+            Op::BXC if operand == 0 => mnemonic.to_string(),
+            Op::JNZ if targets.contains(&operand) => format!("{mnemonic} {}", label(operand)),
+            _ if op.takes_combo() => format!("{mnemonic} {}", combo_name(operand)),
+            _ => format!("{mnemonic} {operand}"),
+        };
+        writeln!(
+            source,
+            "    {instruction:<12}; {pointer}: {opcode},{operand}"
+        )
+        .unwrap();
+    }
+    if targets.contains(&end) {
+        writeln!(source, "{}:", label(end)).unwrap();
+    }
+    source
+}
@@ -0,0 +1,217 @@
+use super::vm::{Op, Program};
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    ops::{BitXor, Shr},
+};
+
+// a register's value in terms of the registers at the start of a loop iteration
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Expr {
+    A,
+    B,
+    C,
+    Literal(u64),
+    Mod8(Box<Expr>),
+    Xor(Box<Expr>, Box<Expr>),
+    Shr(Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    pub fn mod8(self) -> Self {
+        match self {
+            Self::Literal(n) => Self::Literal(n % 8),
+            Self::Mod8(_) => self,
+            _ => Self::Mod8(self.into()),
+        }
+    }
+
+    // whether the value depends on `register` from before the loop iteration
+    pub fn reads(&self, register: &Self) -> bool {
+        match self {
+            Self::A | Self::B | Self::C => self == register,
+            Self::Literal(_) => false,
+            Self::Mod8(expr) => expr.reads(register),
+            Self::Xor(x, y) | Self::Shr(x, y) => x.reads(register) || y.reads(register),
+        }
+    }
+
+    // whether the value depends on B or C carried over from the previous iteration
+    pub fn reads_state(&self) -> bool {
+        self.reads(&Self::B) || self.reads(&Self::C)
+    }
+
+    pub fn eval(&self, registers @ [a, b, c]: [u64; 3]) -> u64 {
+        match self {
+            Self::A => a,
+            Self::B => b,
+            Self::C => c,
+            &Self::Literal(n) => n,
+            Self::Mod8(expr) => expr.eval(registers) % 8,
+            Self::Xor(x, y) => x.eval(registers) ^ y.eval(registers),
+            Self::Shr(x, y) => {
+                let shift = y.eval(registers).try_into().unwrap_or(u32::MAX);
+                x.eval(registers).checked_shr(shift).unwrap_or(0)
+            }
+        }
+    }
+}
+
+impl BitXor for Expr {
+    type Output = Self;
+
+    fn bitxor(self, other: Self) -> Self::Output {
+        match (self, other) {
+            (Self::Literal(x), Self::Literal(y)) => Self::Literal(x ^ y),
+            (expr, Self::Literal(0)) | (Self::Literal(0), expr) => expr,
+            (Self::Xor(expr, x), Self::Literal(y)) if matches!(*x, Self::Literal(_)) => {
+                *expr ^ (*x ^ Self::Literal(y))
+            }
+            (x, y) => Self::Xor(x.into(), y.into()),
+        }
+    }
+}
+
+impl Shr for Expr {
+    type Output = Self;
+
+    fn shr(self, other: Self) -> Self::Output {
+        match (self, other) {
+            (Self::Literal(x), Self::Literal(y)) => {
+                Self::Literal(x.checked_shr(y as u32).unwrap_or(0))
+            }
+            (expr, Self::Literal(0)) => expr,
+            (Self::Shr(expr, x), Self::Literal(y)) if matches!(*x, Self::Literal(_)) => {
+                let Self::Literal(x) = *x else { unreachable!() };
+                *expr >> Self::Literal(x + y)
+            }
+            (x, y) => Self::Shr(x.into(), y.into()),
+        }
+    }
+}
+
+impl Display for Expr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let operand = |f: &mut Formatter<'_>, expr: &Self| match expr {
+            Self::A | Self::B | Self::C | Self::Literal(_) => write!(f, "{expr}"),
+            _ => write!(f, "({expr})"),
+        };
+        match self {
+            Self::A => write!(f, "a"),
+            Self::B => write!(f, "b"),
+            Self::C => write!(f, "c"),
+            Self::Literal(n) => write!(f, "{n}"),
+            Self::Mod8(expr) => {
+                operand(f, expr)?;
+                write!(f, " % 8")
+            }
+            Self::Xor(x, y) => {
+                operand(f, x)?;
+                write!(f, " ^ ")?;
+                operand(f, y)
+            }
+            Self::Shr(x, y) => {
+                operand(f, x)?;
+                write!(f, " >> ")?;
+                operand(f, y)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecompileError {
+    NoFinalJump,
+    ExtraJump,
+}
+
+impl Display for DecompileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoFinalJump => write!(f, "program doesn't end with `jnz 0`"),
+            Self::ExtraJump => write!(f, "program jumps somewhere other than the final `jnz 0`"),
+        }
+    }
+}
+
+impl Error for DecompileError {}
+
+// one iteration of a program shaped like `do { ... } while a != 0`
+#[derive(Clone, Debug)]
+pub struct Loop {
+    pub body: Vec<Op>,
+    // every value output by one iteration, in order
+    pub outputs: Vec<Expr>,
+    // the registers at the end of the iteration
+    pub registers: [Expr; 3],
+}
+
+pub fn decompile(program: &Program) -> Result<Loop, DecompileError> {
+    let Some((&Op(Op::JNZ, 0), body)) = program.split_last() else {
+        return Err(DecompileError::NoFinalJump);
+    };
+    if body.iter().any(|&Op(opcode, _)| opcode == Op::JNZ) {
+        return Err(DecompileError::ExtraJump);
+    }
+
+    let [mut a, mut b, mut c] = [Expr::A, Expr::B, Expr::C];
+    let mut outputs = vec![];
+    for &op @ Op(opcode, operand) in body {
+        let combo = match operand {
+            4 => a.clone(),
+            5 => b.clone(),
+            6 => c.clone(),
+            _ => Expr::Literal(operand),
+        };
+        let literal = Expr::Literal(operand);
+        match opcode {
+            Op::ADV => a = a >> combo,
+            Op::BXL => b = b ^ literal,
+            Op::BST => b = combo.mod8(),
+            Op::BXC => b = b ^ c.clone(),
+            Op::OUT => outputs.push(combo.mod8()),
+            Op::BDV => b = a.clone() >> combo,
+            Op::CDV => c = a.clone() >> combo,
+            _ => unreachable!("invalid {op:?}"),
+        }
+    }
+    Ok(Loop {
+        body: body.into(),
+        outputs,
+        registers: [a, b, c],
+    })
+}
+
+impl Display for Loop {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "do {{")?;
+        for &op @ Op(opcode, operand) in &self.body {
+            let combo = match operand {
+                4 => "a".into(),
+                5 => "b".into(),
+                6 => "c".into(),
+                _ => operand.to_string(),
+            };
+            match opcode {
+                Op::ADV => writeln!(f, "    a = a >> {combo}"),
+                Op::BXL => writeln!(f, "    b = b ^ {operand}"),
+                Op::BST => writeln!(f, "    b = {combo} % 8"),
+                Op::BXC => writeln!(f, "    b = b ^ c"),
+                Op::OUT => writeln!(f, "    out({combo} % 8)"),
+                Op::BDV => writeln!(f, "    b = a >> {combo}"),
+                Op::CDV => writeln!(f, "    c = a >> {combo}"),
+                _ => unreachable!("invalid {op:?}"),
+            }?;
+        }
+        writeln!(f, "}} while a != 0")?;
+        writeln!(f)?;
+        writeln!(f, "where each iteration")?;
+        for output in &self.outputs {
+            writeln!(f, "    outputs {output}")?;
+        }
+        for (name, register) in ["a", "b", "c"].iter().zip(&self.registers) {
+            writeln!(f, "    sets {name} = {register}")?;
+        }
+        Ok(())
+    }
+}
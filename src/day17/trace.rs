@@ -0,0 +1,141 @@
+use super::vm::{Machine, Op, Registers, Step, VmError};
+use std::fmt::Write;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Breakpoint {
+    // stops before the instruction at this pc runs
+    Pc(usize),
+    // stops once this many values have been output
+    Outputs(usize),
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Stop {
+    Halted,
+    Breakpoint(Breakpoint),
+}
+
+// the state of the machine before running `op`, and what it output
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct TraceEntry {
+    pub step: usize,
+    pub pc: usize,
+    pub op: Op,
+    pub registers: Registers,
+    pub output: Option<u64>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Tracer<'p> {
+    machine: Machine<'p>,
+    breakpoints: Vec<Breakpoint>,
+    trace: Vec<TraceEntry>,
+    outputs: Vec<u64>,
+}
+
+impl<'p> Tracer<'p> {
+    pub fn new(machine: Machine<'p>) -> Self {
+        Self {
+            machine,
+            breakpoints: vec![],
+            trace: vec![],
+            outputs: vec![],
+        }
+    }
+
+    pub fn break_at(&mut self, breakpoint: Breakpoint) {
+        self.breakpoints.push(breakpoint);
+    }
+
+    pub fn clear_breakpoints(&mut self) {
+        self.breakpoints.clear();
+    }
+
+    pub fn machine(&self) -> &Machine<'p> {
+        &self.machine
+    }
+
+    pub fn trace(&self) -> &[TraceEntry] {
+        &self.trace
+    }
+
+    pub fn outputs(&self) -> &[u64] {
+        &self.outputs
+    }
+
+    // runs one instruction, returning its trace entry, or `None` once the program has halted
+    pub fn step(&mut self) -> Result<Option<&TraceEntry>, VmError> {
+        let Some(op) = self.machine.current() else {
+            return Ok(None);
+        };
+        let (pc, registers) = (self.machine.pc, self.machine.registers);
+        let output = match self.machine.step()? {
+            Step::Output(output) => Some(output),
+            Step::Continue => None,
+            Step::Halted => return Ok(None),
+        };
+        self.outputs.extend(output);
+        self.trace.push(TraceEntry {
+            step: self.trace.len(),
+            pc,
+            op,
+            registers,
+            output,
+        });
+        Ok(self.trace.last())
+    }
+
+    // runs at least one instruction, then until a breakpoint is hit or the program halts
+    pub fn run(&mut self) -> Result<Stop, VmError> {
+        if self.step()?.is_none() {
+            return Ok(Stop::Halted);
+        }
+        loop {
+            let hit = self
+                .breakpoints
+                .iter()
+                .find(|&&breakpoint| match breakpoint {
+                    Breakpoint::Pc(pc) => self.machine.pc == pc,
+                    Breakpoint::Outputs(count) => {
+                        self.outputs.len() == count && self.trace.last().unwrap().output.is_some()
+                    }
+                });
+            if let Some(&breakpoint) = hit {
+                return Ok(Stop::Breakpoint(breakpoint));
+            }
+            if self.step()?.is_none() {
+                return Ok(Stop::Halted);
+            }
+        }
+    }
+
+    // one line per step, with the registers in octal since the puzzle works in octal digits,
+    // padded to fit `0o` and the 22 digits of `u64::MAX`
+    pub fn dump(&self) -> String {
+        let mut dump = format!(
+            "{:>6} {:>3}  {:<8} {:>24} {:>24} {:>24}  out\n",
+            "step", "pc", "op", "a", "b", "c"
+        );
+        for &TraceEntry {
+            step,
+            pc,
+            op,
+            registers: [a, b, c],
+            output,
+        } in &self.trace
+        {
+            let op = format!("{op:?}");
+            write!(
+                dump,
+                "{step:>6} {pc:>3}  {op:<8} {a:>#24o} {b:>#24o} {c:>#24o}"
+            )
+            .unwrap();
+            match output {
+                Some(output) => writeln!(dump, "  {output}"),
+                None => writeln!(dump),
+            }
+            .unwrap();
+        }
+        dump
+    }
+}
@@ -0,0 +1,264 @@
+use crate::utils::*;
+use std::{
+    error::Error,
+    fmt::{self, Debug, Display, Formatter},
+    iter,
+    ops::Deref,
+};
+
+pub type Registers = [u64; 3];
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Op(pub u64, pub u64);
+
+impl Op {
+    pub const ADV: u64 = 0;
+    pub const BXL: u64 = 1;
+    pub const BST: u64 = 2;
+    pub const JNZ: u64 = 3;
+    pub const BXC: u64 = 4;
+    pub const OUT: u64 = 5;
+    pub const BDV: u64 = 6;
+    pub const CDV: u64 = 7;
+
+    pub fn takes_combo(self) -> bool {
+        matches!(
+            self.0,
+            Self::ADV | Self::BST | Self::OUT | Self::BDV | Self::CDV
+        )
+    }
+}
+
+impl Debug for Op {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Self::ADV => write!(f, "ADV({})", self.1),
+            Self::BXL => write!(f, "BXL({})", self.1),
+            Self::BST => write!(f, "BST({})", self.1),
+            Self::JNZ => write!(f, "JNZ({})", self.1),
+            Self::BXC => write!(f, "BXC"),
+            Self::OUT => write!(f, "OUT({})", self.1),
+            Self::BDV => write!(f, "BDV({})", self.1),
+            Self::CDV => write!(f, "CDV({})", self.1),
+            _ => write!(f, "???({}, {})", self.0, self.1),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VmError {
+    Parse(String),
+    OddLength,
+    InvalidOctal { pc: usize, op: Op },
+    InvalidCombo { pc: usize, op: Op },
+    OddJump { pc: usize, target: u64 },
+    InstructionLimit(usize),
+}
+
+impl Display for VmError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(s) => write!(f, "invalid octal {s:?} in program"),
+            Self::OddLength => write!(f, "program has an opcode without an operand"),
+            Self::InvalidOctal { pc, op } => write!(f, "{op:?} at {pc} isn't made of octals"),
+            Self::InvalidCombo { pc, op } => write!(f, "{op:?} at {pc} uses reserved combo 7"),
+            Self::OddJump { pc, target } => {
+                write!(
+                    f,
+                    "jump at {pc} targets the middle of an instruction ({target})"
+                )
+            }
+            Self::InstructionLimit(limit) => write!(f, "program ran past {limit} instructions"),
+        }
+    }
+}
+
+impl Error for VmError {}
+
+// a program that has been checked to never reach an invalid instruction
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Program(Vec<Op>);
+
+impl Program {
+    pub fn new(ops: Vec<Op>) -> Result<Self, VmError> {
+        for (pc, &op) in ops.iter().enumerate() {
+            let Op(opcode, operand) = op;
+            if opcode > 7 || operand > 7 {
+                return Err(VmError::InvalidOctal { pc, op });
+            }
+            if op.takes_combo() && operand == 7 {
+                return Err(VmError::InvalidCombo { pc, op });
+            }
+            if opcode == Op::JNZ && !operand.is_multiple_of(2) {
+                return Err(VmError::OddJump {
+                    pc,
+                    target: operand,
+                });
+            }
+        }
+        Ok(Self(ops))
+    }
+
+    // comma separated octals, as in `Program: 0,3,5,4,3,0`
+    pub fn parse(s: &str) -> Result<Self, VmError> {
+        let octals: Vec<u64> = s
+            .trim()
+            .split(',')
+            .map(|octal| try_parse(octal.trim()).filter(|(_, rest)| rest.is_empty()))
+            .map(|octal| {
+                octal
+                    .map(|(octal, _)| octal)
+                    .ok_or_else(|| VmError::Parse(s.into()))
+            })
+            .try_collect()?;
+        if !octals.len().is_multiple_of(2) {
+            return Err(VmError::OddLength);
+        }
+        Self::new(octals.chunks(2).map(|op| Op(op[0], op[1])).collect())
+    }
+
+    pub fn octals(&self) -> impl Iterator<Item = u64> + use<'_> {
+        self.0
+            .iter()
+            .flat_map(|&Op(opcode, operand)| [opcode, operand])
+    }
+
+    pub fn machine(&self, registers: Registers) -> Machine<'_> {
+        Machine::new(self, registers)
+    }
+}
+
+impl Deref for Program {
+    type Target = [Op];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Step {
+    Output(u64),
+    Continue,
+    Halted,
+}
+
+#[derive(Clone, Debug)]
+pub struct Machine<'p> {
+    pub registers: Registers,
+    // index into the program's ops, so half of the puzzle's instruction pointer
+    pub pc: usize,
+    program: &'p Program,
+    steps: usize,
+    limit: Option<usize>,
+}
+
+impl<'p> Machine<'p> {
+    pub fn new(program: &'p Program, registers: Registers) -> Self {
+        Self {
+            registers,
+            pc: 0,
+            program,
+            steps: 0,
+            limit: None,
+        }
+    }
+
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn program(&self) -> &'p Program {
+        self.program
+    }
+
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    pub fn halted(&self) -> bool {
+        self.pc >= self.program.len()
+    }
+
+    pub fn current(&self) -> Option<Op> {
+        self.program.get(self.pc).copied()
+    }
+
+    fn combo(&self, combo: u64) -> u64 {
+        let [a, b, c] = self.registers;
+        match combo {
+            0..=3 => combo,
+            4 => a,
+            5 => b,
+            6 => c,
+            // programs are validated on construction
+            7.. => unreachable!(),
+        }
+    }
+
+    pub fn step(&mut self) -> Result<Step, VmError> {
+        let Some(op @ Op(opcode, operand)) = self.current() else {
+            return Ok(Step::Halted);
+        };
+        if self.limit.is_some_and(|limit| self.steps >= limit) {
+            return Err(VmError::InstructionLimit(self.steps));
+        }
+        self.steps += 1;
+        let combo = if op.takes_combo() {
+            self.combo(operand)
+        } else {
+            operand
+        };
+        let [a, b, c] = self.registers;
+        // shifting by 64 or more clears the register rather than overflowing
+        let shifted = a
+            .checked_shr(combo.try_into().unwrap_or(u32::MAX))
+            .unwrap_or(0);
+        let mut step = Step::Continue;
+        match opcode {
+            Op::ADV => self.registers[0] = shifted,
+            Op::BXL => self.registers[1] = b ^ operand,
+            Op::BST => self.registers[1] = combo % 8,
+            Op::JNZ if a != 0 => {
+                self.pc = operand as usize / 2;
+                return Ok(Step::Continue);
+            }
+            Op::JNZ => {}
+            Op::BXC => self.registers[1] = b ^ c,
+            Op::OUT => step = Step::Output(combo % 8),
+            Op::BDV => self.registers[1] = shifted,
+            Op::CDV => self.registers[2] = shifted,
+            _ => unreachable!("invalid {op:?}"),
+        }
+        self.pc += 1;
+        Ok(step)
+    }
+
+    // the next output, or `None` once the program halts
+    pub fn run_until_output(&mut self) -> Result<Option<u64>, VmError> {
+        loop {
+            match self.step()? {
+                Step::Output(output) => return Ok(Some(output)),
+                Step::Continue => {}
+                Step::Halted => return Ok(None),
+            }
+        }
+    }
+
+    pub fn outputs(&mut self) -> impl Iterator<Item = Result<u64, VmError>> + use<'_, 'p> {
+        let mut failed = false;
+        iter::from_fn(move || {
+            if failed {
+                return None;
+            }
+            let output = self.run_until_output().transpose();
+            failed = matches!(output, Some(Err(_)));
+            output
+        })
+    }
+
+    pub fn run(&mut self) -> Result<Vec<u64>, VmError> {
+        self.outputs().collect()
+    }
+}
@@ -1,8 +1,7 @@
 use crate::utils::*;
-use std::mem;
 
-const DIMS: Index = (71, 71);
-const BOTTOM_RIGHT: Index = (DIMS.0 - 1, DIMS.1 - 1);
+const DIMS: Index = Point::new(71, 71);
+const BOTTOM_RIGHT: Index = Point::new(DIMS.row - 1, DIMS.column - 1);
 const STEPS: usize = 1024;
 
 const _SAMPLE: &str = "\
@@ -34,10 +33,8 @@ const _SAMPLE: &str = "\
 
 fn bytes(input: &str) -> impl Iterator<Item = Index> + use<'_> {
     input.lines().map(|line| {
-        let (x, line) = parse(line);
-        let line = &line[1..];
-        let (y, _) = parse(line);
-        (y, x)
+        let [x, y] = parse_n(line).unwrap();
+        Point::from_xy(x, y)
     })
 }
 
@@ -46,50 +43,69 @@ pub fn part1(input: &str) -> Answer {
     for byte in bytes(input).take(STEPS) {
         space.set(byte, '#');
     }
-    let mut probes = HashSet::from_iter([(0, 0)]);
-    let mut new_probes = HashSet::default();
-    for step in 1.. {
-        for neighbor in probes.drain().flat_map(dirs) {
-            if space.get(neighbor).map(Into::into) == Some(' ') {
-                new_probes.insert(neighbor);
-                space.set(neighbor, '#');
+    escape(&space).cost().unwrap().into()
+}
+
+// the byte that first cuts off the exit, found by clearing bytes in reverse until the
+// corners are joined again, or `None` if the exit is reachable with every byte fallen
+fn first_blocking(bytes: &[Index]) -> Option<Index> {
+    let flat = |index| flat_index(index, DIMS).unwrap();
+    let mut space = GridOwned::new_dims(DIMS);
+    // bytes can fall on the same cell more than once, which stays blocked until all are cleared
+    let mut fallen = vec![0; (DIMS.row * DIMS.column) as _];
+    for &byte in bytes {
+        space.set(byte, '#');
+        fallen[flat(byte)] += 1;
+    }
+
+    let mut regions = UnionFind::new((DIMS.row * DIMS.column) as _);
+    let clear = |space: &mut GridOwned, regions: &mut UnionFind, index| {
+        space.set(index, ' ');
+        for neighbor in dirs(index) {
+            if space.get(neighbor).is_some_and(|b| b == ' ') {
+                regions.union(flat(index), flat(neighbor));
             }
         }
-        mem::swap(&mut probes, &mut new_probes);
-        if probes.contains(&BOTTOM_RIGHT) {
-            return Answer::Number(step);
+    };
+    for index in space.indices() {
+        if space.get(index).is_some_and(|b| b == ' ') {
+            clear(&mut space, &mut regions, index);
         }
     }
-    unreachable!()
+
+    let connected =
+        |regions: &mut UnionFind| regions.connected(flat(Point::ZERO), flat(BOTTOM_RIGHT));
+    if connected(&mut regions) {
+        return None;
+    }
+    bytes.iter().rev().copied().find(|&byte| {
+        fallen[flat(byte)] -= 1;
+        fallen[flat(byte)] == 0 && {
+            clear(&mut space, &mut regions, byte);
+            connected(&mut regions)
+        }
+    })
 }
 
 pub fn part2(input: &str) -> Answer {
-    let space = GridOwned::new_dims(DIMS);
-    let (_, (col, row)) = bytes(input)
-        .scan(space, |space, byte| {
-            space.set(byte, '#');
-            Some((space.clone(), byte))
-        })
-        .collect_vec()
-        .into_par_iter()
-        .by_exponential_blocks()
-        .find_first(|(space, _)| {
-            let mut space = space.clone();
-            let (mut probes, mut new_probes) = (HashSet::from_iter([(0, 0)]), HashSet::default());
-            while !probes.contains(&BOTTOM_RIGHT) {
-                for neighbor in probes.drain().flat_map(dirs) {
-                    if space.get(neighbor).map(Into::into) == Some(' ') {
-                        new_probes.insert(neighbor);
-                        space.set(neighbor, 'X');
-                    }
-                }
-                mem::swap(&mut probes, &mut new_probes);
-                if probes.is_empty() {
-                    return true;
-                }
-            }
-            false
-        })
-        .unwrap();
-    Answer::String(format!("{row},{col}"))
+    let bytes = bytes(input).collect_vec();
+    match first_blocking(&bytes) {
+        Some(byte) => {
+            let (x, y) = byte.to_xy();
+            Answer::String(format!("{x},{y}"))
+        }
+        None => Answer::String("the exit is never cut off".into()),
+    }
+}
+
+fn escape(space: &GridOwned) -> search::Search<Index, usize> {
+    search::bfs(
+        Point::ZERO,
+        |&pos| {
+            dirs(pos)
+                .into_iter()
+                .filter(|&neighbor| space.get(neighbor).is_some_and(|b| b == ' '))
+        },
+        |&pos| pos == BOTTOM_RIGHT,
+    )
 }
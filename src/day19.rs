@@ -48,32 +48,25 @@ pub fn part2(input: &str) -> Answer {
         towel: &'a [u8],
         patterns: &HashSet<&[u8]>,
         pattern_max: usize,
-        memo: &mut HashMap<&'a [u8], u64>,
+        memo: &mut Memo<&'a [u8], u64>,
     ) -> u64 {
-        if let Some(&count) = memo.get(towel) {
-            count
-        } else {
-            let count = if towel.is_empty() {
+        memo.get_or_compute(towel, |memo| {
+            if towel.is_empty() {
                 1
             } else {
                 (1..=pattern_max)
                     .rev()
                     .filter_map(|pattern_len| towel.split_at_checked(pattern_len))
-                    .filter_map(|(pattern, rest)| {
-                        patterns
-                            .contains(pattern)
-                            .then(|| count_towels(rest, patterns, pattern_max, memo))
-                    })
+                    .filter(|(pattern, _)| patterns.contains(pattern))
+                    .map(|(_, rest)| count_towels(rest, patterns, pattern_max, memo))
                     .sum()
-            };
-            memo.insert(towel, count);
-            count
-        }
+            }
+        })
     }
 
     let (patterns, towels) = parse_towels(input);
     let pattern_max = patterns.iter().map(|pattern| pattern.len()).max().unwrap();
-    let mut memo = HashMap::default();
+    let mut memo = Memo::new();
     towels
         .map(|towel| count_towels(towel, &patterns, pattern_max, &mut memo))
         .sum::<u64>()
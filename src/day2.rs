@@ -38,10 +38,7 @@ pub fn part1(input: &str) -> Answer {
     input
         .lines()
         .filter(|levels| {
-            let report: ArrayVec<[u32; 8]> = levels
-                .split_ascii_whitespace()
-                .map(|level| parse::<u32>(level).0)
-                .collect();
+            let report: ArrayVec<[u32; 8]> = parse_all(levels).collect();
             valid_report(&report)
         })
         .count()
@@ -52,10 +49,7 @@ pub fn part2(input: &str) -> Answer {
     input
         .lines()
         .filter(|levels| {
-            let mut report: ArrayVec<[u32; 8]> = levels
-                .split_ascii_whitespace()
-                .map(|level| parse::<u32>(level).0)
-                .collect();
+            let mut report: ArrayVec<[u32; 8]> = parse_all(levels).collect();
             valid_report(&report)
                 || (0..report.len()).any(|i| {
                     let removed = report.remove(i);
@@ -35,13 +35,10 @@ pub fn part1(input: &str) -> Answer {
         if unsafe { grid.get(pos).unwrap_unchecked() } == 'E' {
             break;
         }
-        for &dir in [Dir::North, Dir::East, Dir::South, Dir::West]
-            .iter()
-            .filter(|&&dir| dir != backwards)
-        {
+        for &dir in Dir::ALL.iter().filter(|&&dir| dir != backwards) {
             if unsafe { grid.get(pos + dir).unwrap_unchecked() } != '#' {
                 pos += dir;
-                backwards = dir.clockwise().clockwise();
+                backwards = dir.opposite();
                 break;
             }
         }
@@ -54,10 +51,7 @@ pub fn part1(input: &str) -> Answer {
     while unsafe { grid.get(pos).unwrap_unchecked() } != 'E' {
         let &current = tiles.get(&pos).unwrap();
         let mut progress = Dir::North;
-        for &dir in [Dir::North, Dir::East, Dir::South, Dir::West]
-            .iter()
-            .filter(|&&dir| dir != backwards)
-        {
+        for &dir in Dir::ALL.iter().filter(|&&dir| dir != backwards) {
             if unsafe { grid.get(pos + dir).unwrap_unchecked() } == '#' {
                 if grid
                     .get(pos + dir + dir)
@@ -74,7 +68,7 @@ pub fn part1(input: &str) -> Answer {
             }
         }
         pos += progress;
-        backwards = progress.clockwise().clockwise();
+        backwards = progress.opposite();
     }
 
     count.into()
@@ -94,13 +88,10 @@ pub fn part2(input: &str) -> Answer {
         if unsafe { grid.get(pos).unwrap_unchecked() } == 'E' {
             break;
         }
-        for &dir in [Dir::North, Dir::East, Dir::South, Dir::West]
-            .iter()
-            .filter(|&&dir| dir != backwards)
-        {
+        for &dir in Dir::ALL.iter().filter(|&&dir| dir != backwards) {
             if unsafe { grid.get(pos + dir).unwrap_unchecked() } != '#' {
                 pos += dir;
-                backwards = dir.clockwise().clockwise();
+                backwards = dir.opposite();
                 break;
             }
         }
@@ -109,14 +100,11 @@ pub fn part2(input: &str) -> Answer {
     tiles
         .par_iter()
         .enumerate()
-        .flat_map(|(a, &(i_y, i_x))| {
-            tiles[a as usize..]
-                .par_iter()
-                .enumerate()
-                .filter(move |&(b, &(j_y, j_x))| {
-                    let dist = i_y.abs_diff(j_y) + i_x.abs_diff(j_x);
-                    b as i64 - dist as i64 >= THRESHOLD && dist <= 20
-                })
+        .flat_map(|(a, &i)| {
+            tiles[a..].par_iter().enumerate().filter(move |&(b, &j)| {
+                let dist = i.manhattan(j);
+                b as i64 - dist as i64 >= THRESHOLD && dist <= 20
+            })
         })
         .count()
         .into()
@@ -38,27 +38,21 @@ pub fn part2(input: &str) -> Answer {
                 .map(|secret| (secret % 10) as i64)
                 .tuple_windows()
         })
-        .flat_map(|windows| {
-            windows.fold(HashMap::default(), |mut diffs, (s0, s1, s2, s3, s4)| {
-                diffs
-                    .entry([s1 - s0, s2 - s1, s3 - s2, s4 - s3])
-                    .or_insert(s4);
-                diffs
+        .map(|windows| {
+            windows.fold(Counter::default(), |mut sales, (s0, s1, s2, s3, s4)| {
+                let changes = [s1 - s0, s2 - s1, s3 - s2, s4 - s3];
+                // the monkey sells at the first occurrence of the changes
+                if !sales.contains_key(&changes) {
+                    sales.add_n(changes, s4 as u64);
+                }
+                sales
             })
         })
-        .fold(HashMap::default, |mut collected, (changes, bananas)| {
-            *collected.entry(changes).or_insert(0) += bananas;
-            collected
-        })
-        .reduce(HashMap::default, |mut left, right| {
-            for (changes, bananas) in right {
-                *left.entry(changes).or_insert(0) += bananas;
-            }
+        .reduce(Counter::default, |mut left, right| {
+            left += right;
             left
         })
-        .values()
-        .copied()
-        .max()
-        .unwrap()
+        .most_common(1)[0]
+        .1
         .into()
 }
@@ -1,5 +1,13 @@
 use crate::utils::*;
-use std::{cell::Cell, str};
+use circuit::{Circuit, CircuitError};
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    iter,
+};
+
+pub mod circuit;
+pub mod export;
 
 const _SAMPLE: &str = "\
 x00: 1
@@ -50,37 +58,32 @@ hwm AND bqk -> z03
 tgd XOR rvg -> z12
 tnw OR pbm -> gnj";
 
-#[derive(Debug)]
-enum Gate {
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Gate {
     And,
     Or,
     Xor,
 }
 
 #[derive(Debug)]
-enum Wire<'a> {
+pub enum Wire<'a> {
     In {
         state: bool,
     },
     Out {
-        state: Cell<Option<bool>>,
         gate: Gate,
         left: &'a str,
         right: &'a str,
     },
 }
 
-fn parse_system(input: &str) -> HashMap<&str, Wire> {
+pub fn parse_system(input: &str) -> HashMap<&str, Wire<'_>> {
     let (wires, gates) = input.split_once("\n\n").unwrap();
     let mut out = HashMap::default();
 
     for wire in wires.lines() {
-        out.insert(
-            &wire[..3],
-            Wire::In {
-                state: &wire[5..] == "1",
-            },
-        );
+        let (name, state): (_, u8) = scan!("{}: {}", wire).unwrap();
+        out.insert(name, Wire::In { state: state == 1 });
     }
 
     for gate in gates.lines() {
@@ -92,131 +95,332 @@ fn parse_system(input: &str) -> HashMap<&str, Wire> {
             "XOR" => Gate::Xor,
             _ => unreachable!(),
         };
-        out.insert(
-            output,
-            Wire::Out {
-                state: Default::default(),
-                gate,
-                left,
-                right,
-            },
-        );
+        out.insert(output, Wire::Out { gate, left, right });
     }
 
     out
 }
 
-pub fn part1(input: &str) -> Answer {
-    fn calc(system: &HashMap<&str, Wire>, name: &str) -> Option<bool> {
-        match system.get(name)? {
-            &Wire::In { state } => Some(state),
-            Wire::Out { state, .. } if state.get().is_some() => state.get(),
-            Wire::Out {
-                state,
-                gate,
-                left,
-                right,
-            } => {
-                let (left, right) = (calc(system, left)?, calc(system, right)?);
-                let result = Some(match gate {
-                    Gate::And => left & right,
-                    Gate::Or => left | right,
-                    Gate::Xor => left ^ right,
-                });
-                state.set(result);
-                result
-            }
+// the operands given by the input wires' initial states, once `widths` has checked that
+// they fit in 64 bits
+fn operands(wires: &HashMap<&str, Wire>) -> [u64; 2] {
+    let mut operands = [0, 0];
+    for (&name, wire) in wires {
+        let operand = match name.chars().next() {
+            Some('x') => 0,
+            Some('y') => 1,
+            _ => continue,
+        };
+        if let (&Wire::In { state }, Some(bit)) = (wire, bit(name)) {
+            operands[operand] |= (state as u64) << bit;
         }
     }
+    operands
+}
 
-    let system = parse_system(input);
-    let mut out = 0;
-    for n in 0.. {
-        let name = [b'z', n / 10 + b'0', n % 10 + b'0'];
-        let name = str::from_utf8(&name).unwrap();
-        match calc(&system, name) {
-            Some(true) => out += 2u64.pow(n as _),
-            Some(false) => {}
-            None => return out.into(),
-        }
+pub fn part1(input: &str) -> Answer {
+    let wires = parse_system(input);
+    widths(&wires).unwrap_or_else(|err| panic!("{err}"));
+    let [x, y] = operands(&wires);
+    let circuit = Circuit::compile(&wires, &[]).unwrap_or_else(|err| panic!("{err}"));
+    circuit.eval(x, y).into()
+}
+
+// the bit position of an `x`, `y` or `z` wire
+fn bit(name: &str) -> Option<u32> {
+    let digits = name.strip_prefix(['x', 'y', 'z'])?;
+    try_parse(digits)
+        .filter(|(_, rest)| rest.is_empty())
+        .map(|(bit, _)| bit)
+}
+
+// the `x`, `y` or `z` wires, indexed by their bit
+fn numbered<'a>(wires: &HashMap<&'a str, Wire<'a>>, prefix: char) -> Vec<&'a str> {
+    wires
+        .keys()
+        .copied()
+        .filter(|name| name.starts_with(prefix) && bit(name).is_some())
+        .sorted_unstable_by_key(|&name| bit(name))
+        .collect()
+}
+
+// the widths of the `x`, `y` and `z` wires, which each have to be numbered from 0 up and fit
+// in 64 bits
+fn widths(wires: &HashMap<&str, Wire>) -> Result<[usize; 3], CircuitError> {
+    let mut widths = [0; 3];
+    for (prefix, width) in ['x', 'y', 'z'].into_iter().zip(&mut widths) {
+        let numbered = numbered(wires, prefix);
+        circuit::check_bits(prefix, numbered.iter().map(|name| bit(name).unwrap()))?;
+        *width = numbered.len();
     }
-    unreachable!()
+    Ok(widths)
 }
 
-pub fn part2(input: &str) -> Answer {
-    let wires = parse_system(input);
+// the width of an adder's operands, which have to match and leave room for the carry in a
+// 64 bit sum, so operands are at most 63 bits
+fn adder_width(wires: &HashMap<&str, Wire>) -> Result<usize, CircuitError> {
+    let [x, y, z] = widths(wires)?;
+    if x != y || z != x + 1 {
+        return Err(CircuitError::Widths { x, y, z });
+    }
+    Ok(x)
+}
 
-    macro_rules! make_names {
-        ($($name:ident = name($letter:expr, $n:expr);)*) => {
-            $(
-                let name = [$letter, $n / 10 + b'0', $n % 10 + b'0'];
-                let $name = str::from_utf8(&name).unwrap();
-            )*
-        };
+// wires that are out of place in a ripple-carry adder, where each bit is
+// `z = (x ^ y) ^ carry` and `carry' = (x & y) | ((x ^ y) & carry)`, found by walking up the
+// bits and following the carry; a gate fed by one expected wire and one unexpected wire means
+// the two were swapped, and so does a sum landing anywhere but its `z` wire
+pub fn suspects<'a>(wires: &HashMap<&'a str, Wire<'a>>) -> Vec<&'a str> {
+    let mut gates = HashMap::default();
+    let mut consumers = HashMap::<_, Vec<_>>::default();
+    for (&out, wire) in wires {
+        if let &Wire::Out { gate, left, right } = wire {
+            gates.insert((gate, left.min(right), left.max(right)), out);
+            consumers
+                .entry((gate, left))
+                .or_default()
+                .push((right, out));
+            consumers
+                .entry((gate, right))
+                .or_default()
+                .push((left, out));
+        }
     }
+    let gate = |gate, a: &'a str, b: &'a str| gates.get(&(gate, a.min(b), a.max(b))).copied();
+    let other_input = |gate, input| {
+        consumers
+            .get(&(gate, input))
+            .and_then(|outs| outs.first())
+            .map(|&(other, _)| other)
+    };
 
-    for n in 2..=45 {
-        make_names! {
-            z_name = name(b'z', n);
-            x_name = name(b'x', n);
-            y_name = name(b'y', n);
-            // prev_z_name = name(b'z', n - 1);
-            // prev_x_name = name(b'x', n - 1);
-            // prev_y_name = name(b'y', n - 1);
+    // swapped wires map to each other, so a gate's output is read under its partner's name
+    fn swap<'a>(swapped: &mut HashMap<&'a str, &'a str>, a: &'a str, b: &'a str) {
+        if a != b && !swapped.contains_key(a) && !swapped.contains_key(b) {
+            swapped.insert(a, b);
+            swapped.insert(b, a);
         }
+    }
+    let read = |swapped: &HashMap<_, _>, name| *swapped.get(name).unwrap_or(&name);
+    let mut swapped = HashMap::default();
+    // the walk lines bits up by position, which only works without gaps in the numbering
+    if widths(wires).is_err() {
+        return vec![];
+    }
+    let [x, y, z] = ['x', 'y', 'z'].map(|prefix| numbered(wires, prefix));
 
-        let Wire::Out {
-            gate: Gate::Xor,
-            left,
-            right,
-            ..
-        } = &wires[z_name]
-        else {
-            // println!("0 {z_name}");
+    let mut carry = None;
+    // the walk stops where the structure can't be followed, leaving no carry to check
+    for (i, (&x, &y)) in x.iter().zip(&y).enumerate() {
+        let carry_in = carry.take();
+        let (Some(half), Some(generate)) = (gate(Gate::Xor, x, y), gate(Gate::And, x, y)) else {
+            break;
+        };
+        let Some(&z) = z.get(i) else {
+            break;
+        };
+        if i == 0 {
+            // the first bit has no carry in, so it's a half adder
+            swap(&mut swapped, half, z);
+            carry = Some(generate);
             continue;
+        }
+        let Some(carry_in) = carry_in else {
+            break;
         };
-        let (left_wire, right_wire) = (&wires[left], &wires[right]);
-        let (_, _, in_left, in_right) = match (left_wire, right_wire) {
-            (
-                &Wire::Out {
-                    gate: Gate::Or,
-                    left: carry_left,
-                    right: carry_right,
-                    ..
-                },
-                &Wire::Out {
-                    gate: Gate::Xor,
-                    left: in_left,
-                    right: in_right,
-                    ..
-                },
-            ) => (carry_left, carry_right, in_left, in_right),
-            (
-                &Wire::Out {
-                    gate: Gate::Xor,
-                    left: in_left,
-                    right: in_right,
-                    ..
-                },
-                &Wire::Out {
-                    gate: Gate::Or,
-                    left: carry_left,
-                    right: carry_right,
-                    ..
-                },
-            ) => (carry_left, carry_right, in_left, in_right),
-            _ => {
-                // println!("1 {z_name} {left} {right}");
-                continue;
+
+        // the sum reads both the half sum and the carry, so whichever of them the xor gate
+        // doesn't read was swapped with the wire it reads instead
+        let (mut half_read, mut carry_read) = (read(&swapped, half), read(&swapped, carry_in));
+        if gate(Gate::Xor, half_read, carry_read).is_none() {
+            if let Some(other) = other_input(Gate::Xor, carry_read) {
+                swap(&mut swapped, half, other);
+                half_read = other;
+            } else if let Some(other) = other_input(Gate::Xor, half_read) {
+                swap(&mut swapped, carry_in, other);
+                carry_read = other;
+            } else {
+                break;
             }
+        }
+        let Some(sum) = gate(Gate::Xor, half_read, carry_read) else {
+            break;
         };
-        if (in_left, in_right) != (x_name, y_name) && (in_left, in_right) != (y_name, x_name) {
-            // println!("2 {in_left} {in_right}");
-            continue;
+        swap(&mut swapped, sum, z);
+
+        let Some(propagate) = gate(Gate::And, half_read, carry_read) else {
+            break;
+        };
+        let (mut generate_read, mut propagate_read) =
+            (read(&swapped, generate), read(&swapped, propagate));
+        if gate(Gate::Or, generate_read, propagate_read).is_none() {
+            if let Some(other) = other_input(Gate::Or, generate_read) {
+                swap(&mut swapped, propagate, other);
+                propagate_read = other;
+            } else if let Some(other) = other_input(Gate::Or, propagate_read) {
+                swap(&mut swapped, generate, other);
+                generate_read = other;
+            } else {
+                break;
+            }
         }
+        carry = gate(Gate::Or, generate_read, propagate_read);
+    }
+    // the last carry is the extra output bit
+    if let (Some(carry), Some(&last)) = (carry, z.get(x.len())) {
+        swap(&mut swapped, carry, last);
     }
 
-    "fbq,pbv,qff,qnw,qqp,z16,z23,z36".into()
+    swapped.into_keys().sorted_unstable().collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RepairError {
+    Circuit(CircuitError),
+    NoSuspects,
+    OddSuspects(Vec<String>),
+    TooManySuspects(Vec<String>),
+    NoRepair(Vec<String>),
+}
+
+impl Display for RepairError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Circuit(err) => write!(f, "{err}"),
+            Self::NoSuspects => write!(f, "the adder is wrong, but no wires look swapped"),
+            Self::OddSuspects(suspects) => {
+                write!(f, "can't pair up the suspect wires {}", suspects.join(","))
+            }
+            Self::TooManySuspects(suspects) => write!(
+                f,
+                "too many suspect wires to try pairing up: {}",
+                suspects.join(",")
+            ),
+            Self::NoRepair(suspects) => {
+                write!(f, "no swaps among {} fix the adder", suspects.join(","))
+            }
+        }
+    }
+}
+
+impl Error for RepairError {}
+
+impl From<CircuitError> for RepairError {
+    fn from(err: CircuitError) -> Self {
+        Self::Circuit(err)
+    }
+}
+
+// the swapped pairs, along with the additions the repaired circuit was checked against and
+// how many each output bit got wrong
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Repair<'a> {
+    pub swaps: Vec<[&'a str; 2]>,
+    pub operands: usize,
+    pub errors: Vec<usize>,
+}
+
+impl Repair<'_> {
+    pub fn wires(&self) -> Vec<&str> {
+        self.swaps
+            .iter()
+            .flatten()
+            .copied()
+            .sorted_unstable()
+            .collect()
+    }
+}
+
+impl Display for Repair<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for [a, b] in &self.swaps {
+            writeln!(f, "swap {a} and {b}")?;
+        }
+        let wrong = self.errors.iter().filter(|&&errors| errors > 0).count();
+        write!(
+            f,
+            "checked {} additions, with {wrong} of {} output bits ever wrong",
+            self.operands,
+            self.errors.len()
+        )
+    }
+}
+
+const TRIALS: usize = 128;
+
+// trying every pairing of the suspects grows factorially, so only a few swaps are searched
+const MAX_SUSPECTS: usize = 8;
+
+// the per-bit errors of the circuit with `swaps` made, over all ones (which makes the carry
+// ripple through every bit) followed by random operands, or `None` if it doesn't compile
+fn check(wires: &HashMap<&str, Wire>, swaps: &[[&str; 2]]) -> Option<(usize, Vec<usize>)> {
+    let circuit = Circuit::compile(wires, swaps).ok()?;
+    let mask = circuit::mask(circuit.input_width());
+    let mut rng = Rng::new(circuit.input_width() as u64);
+    let operands = iter::once([mask, mask])
+        .chain(iter::repeat_with(|| [rng.next_u64() & mask, rng.next_u64() & mask]).take(TRIALS))
+        .collect_vec();
+    Some((operands.len(), circuit.errors(&operands)))
+}
+
+fn verify(wires: &HashMap<&str, Wire>, swaps: &[[&str; 2]]) -> bool {
+    check(wires, swaps).is_some_and(|(_, errors)| errors.iter().all(|&errors| errors == 0))
+}
+
+pub fn repair<'a>(wires: &HashMap<&'a str, Wire<'a>>) -> Result<Repair<'a>, RepairError> {
+    fn pair_up<'a>(
+        wires: &HashMap<&'a str, Wire<'a>>,
+        unpaired: &[&'a str],
+        swaps: &mut Vec<[&'a str; 2]>,
+    ) -> bool {
+        let Some((&first, rest)) = unpaired.split_first() else {
+            return verify(wires, swaps);
+        };
+        (0..rest.len()).any(|i| {
+            swaps.push([first, rest[i]]);
+            let unpaired = rest
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .map(|(_, &name)| name)
+                .collect_vec();
+            pair_up(wires, &unpaired, swaps) || {
+                swaps.pop();
+                false
+            }
+        })
+    }
+
+    adder_width(wires)?;
+
+    let suspects = suspects(wires);
+    let names = || suspects.iter().map(|name| name.to_string()).collect();
+    if suspects.is_empty() && !verify(wires, &[]) {
+        return Err(RepairError::NoSuspects);
+    }
+    if !suspects.len().is_multiple_of(2) {
+        return Err(RepairError::OddSuspects(names()));
+    }
+    if suspects.len() > MAX_SUSPECTS {
+        return Err(RepairError::TooManySuspects(names()));
+    }
+    let mut swaps = vec![];
+    if pair_up(wires, &suspects, &mut swaps) {
+        let (operands, errors) = check(wires, &swaps).unwrap();
+        Ok(Repair {
+            swaps,
+            operands,
+            errors,
+        })
+    } else {
+        Err(RepairError::NoRepair(names()))
+    }
+}
+
+pub fn part2(input: &str) -> Answer {
+    let wires = parse_system(input);
+    let repair = repair(&wires).unwrap_or_else(|err| panic!("{err}"));
+    repair.wires().join(",").into()
 }
 
 // struct Wire {
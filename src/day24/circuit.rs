@@ -0,0 +1,259 @@
+use super::{bit, Gate, Wire};
+use crate::utils::*;
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    iter,
+};
+
+pub const LANES: usize = u64::BITS as usize;
+
+// the low `width` bits set, for any width up to 64
+pub fn mask(width: usize) -> u64 {
+    u64::MAX.checked_shr((LANES - width) as u32).unwrap_or(0)
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Step {
+    gate: Gate,
+    left: usize,
+    right: usize,
+    out: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CircuitError {
+    UnknownWire(String),
+    MissingBit(char, u32),
+    TooWide(char, usize),
+    Widths { x: usize, y: usize, z: usize },
+    Cycle(Vec<String>),
+}
+
+impl Display for CircuitError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownWire(name) => write!(f, "no wire named {name:?}"),
+            Self::MissingBit(prefix, bit) => {
+                write!(f, "the {prefix} wires skip bit {bit}")
+            }
+            Self::TooWide(prefix, width) => {
+                write!(f, "the {prefix} wires are {width} bits wide, past {LANES}")
+            }
+            Self::Widths { x, y, z } => write!(
+                f,
+                "{x} and {y} bit operands can't be added into {z} output bits"
+            ),
+            Self::Cycle(names) => write!(f, "wires {} form a cycle", names.join(",")),
+        }
+    }
+}
+
+impl Error for CircuitError {}
+
+// `bits` in order, which have to count up from 0 and fit in 64 lanes
+pub fn check_bits(
+    prefix: char,
+    bits: impl ExactSizeIterator<Item = u32>,
+) -> Result<(), CircuitError> {
+    if bits.len() > LANES {
+        return Err(CircuitError::TooWide(prefix, bits.len()));
+    }
+    (0..)
+        .zip(bits)
+        .find(|&(expected, bit)| bit != expected)
+        .map_or(Ok(()), |(expected, _)| {
+            Err(CircuitError::MissingBit(prefix, expected))
+        })
+}
+
+// the gates in an order where every input is computed before it's used, over wires
+// numbered from 0, so evaluating is a single pass over a flat array
+#[derive(Clone, Debug)]
+pub struct Circuit<'a> {
+    names: Vec<&'a str>,
+    x: Vec<usize>,
+    y: Vec<usize>,
+    z: Vec<usize>,
+    steps: Vec<Step>,
+}
+
+impl<'a> Circuit<'a> {
+    // `swaps` exchanges the output wires of pairs of gates
+    pub fn compile(
+        wires: &HashMap<&'a str, Wire<'a>>,
+        swaps: &[[&str; 2]],
+    ) -> Result<Self, CircuitError> {
+        let names = wires.keys().copied().sorted_unstable().collect_vec();
+        let index: HashMap<_, _> = names
+            .iter()
+            .enumerate()
+            .map(|(i, &name)| (name, i))
+            .collect();
+        let lookup = |name: &str| {
+            index
+                .get(name)
+                .copied()
+                .ok_or_else(|| CircuitError::UnknownWire(name.into()))
+        };
+        let mut rename = HashMap::default();
+        for &[a, b] in swaps {
+            rename.insert(lookup(a)?, lookup(b)?);
+            rename.insert(lookup(b)?, lookup(a)?);
+        }
+        // the wires for each bit of an operand, which have to be numbered from 0 up
+        let numbered = |prefix| {
+            let bits = names
+                .iter()
+                .enumerate()
+                .filter(|(_, name)| name.starts_with(prefix))
+                .filter_map(|(i, &name)| Some((bit(name)?, i)))
+                .sorted_unstable()
+                .collect_vec();
+            check_bits(prefix, bits.iter().map(|&(bit, _)| bit))?;
+            Ok(bits.into_iter().map(|(_, i)| i).collect_vec())
+        };
+
+        let mut steps = vec![];
+        for (&name, wire) in wires {
+            if let &Wire::Out {
+                gate, left, right, ..
+            } = wire
+            {
+                let out = lookup(name)?;
+                steps.push(Step {
+                    gate,
+                    left: lookup(left)?,
+                    right: lookup(right)?,
+                    out: rename.get(&out).copied().unwrap_or(out),
+                });
+            }
+        }
+
+        // kahn's algorithm, where a wire is ready once the gate driving it has run
+        let mut driver = vec![None; names.len()];
+        for (i, step) in steps.iter().enumerate() {
+            driver[step.out] = Some(i);
+        }
+        let mut users = vec![vec![]; names.len()];
+        let mut waiting = vec![0; steps.len()];
+        for (i, step) in steps.iter().enumerate() {
+            for input in [step.left, step.right] {
+                if driver[input].is_some() {
+                    users[input].push(i);
+                    waiting[i] += 1;
+                }
+            }
+        }
+        let mut ready = (0..steps.len()).filter(|&i| waiting[i] == 0).collect_vec();
+        let mut sorted = Vec::with_capacity(steps.len());
+        while let Some(i) = ready.pop() {
+            sorted.push(steps[i]);
+            for &user in &users[steps[i].out] {
+                waiting[user] -= 1;
+                if waiting[user] == 0 {
+                    ready.push(user);
+                }
+            }
+        }
+        if sorted.len() < steps.len() {
+            // the gates left over are cycles plus whatever hangs off them, so trim off
+            // gates until every one left feeds another
+            let mut stuck: HashSet<_> = (0..steps.len()).filter(|&i| waiting[i] > 0).collect();
+            while let Some(&tail) = stuck
+                .iter()
+                .find(|&&i| !users[steps[i].out].iter().any(|user| stuck.contains(user)))
+            {
+                stuck.remove(&tail);
+            }
+            let cycle = stuck
+                .into_iter()
+                .map(|i| names[steps[i].out].to_string())
+                .sorted_unstable()
+                .collect();
+            return Err(CircuitError::Cycle(cycle));
+        }
+
+        Ok(Self {
+            x: numbered('x')?,
+            y: numbered('y')?,
+            z: numbered('z')?,
+            names,
+            steps: sorted,
+        })
+    }
+
+    pub fn input_width(&self) -> usize {
+        self.x.len()
+    }
+
+    pub fn output_width(&self) -> usize {
+        self.z.len()
+    }
+
+    // each input bit holds one bit from every lane, and so does each output bit
+    pub fn eval_lanes(&self, x: &[u64], y: &[u64]) -> Vec<u64> {
+        let mut states = vec![0u64; self.names.len()];
+        for (&wire, &lanes) in iter::zip(&self.x, x).chain(iter::zip(&self.y, y)) {
+            states[wire] = lanes;
+        }
+        for &Step {
+            gate,
+            left,
+            right,
+            out,
+        } in &self.steps
+        {
+            let (left, right) = (states[left], states[right]);
+            states[out] = match gate {
+                Gate::And => left & right,
+                Gate::Or => left | right,
+                Gate::Xor => left ^ right,
+            };
+        }
+        self.z.iter().map(|&wire| states[wire]).collect()
+    }
+
+    // evaluates up to 64 pairs of operands at once, one per lane
+    pub fn eval_many(&self, operands: &[[u64; 2]]) -> Vec<u64> {
+        assert!(operands.len() <= LANES, "only {LANES} lanes per evaluation");
+        let transpose = |width, side: usize| {
+            (0..width)
+                .map(|bit| {
+                    operands
+                        .iter()
+                        .enumerate()
+                        .map(|(lane, operand)| (operand[side] >> bit & 1) << lane)
+                        .fold(0, |lanes, bit| lanes | bit)
+                })
+                .collect_vec()
+        };
+        let z = self.eval_lanes(&transpose(self.x.len(), 0), &transpose(self.y.len(), 1));
+        (0..operands.len())
+            .map(|lane| {
+                z.iter()
+                    .enumerate()
+                    .map(|(bit, &lanes)| (lanes >> lane & 1) << bit)
+                    .fold(0, |sum, bit| sum | bit)
+            })
+            .collect()
+    }
+
+    pub fn eval(&self, x: u64, y: u64) -> u64 {
+        self.eval_many(&[[x, y]])[0]
+    }
+
+    // how often each output bit differs from `x + y` over the `operands`
+    pub fn errors(&self, operands: &[[u64; 2]]) -> Vec<usize> {
+        let mut errors = vec![0; self.z.len()];
+        for chunk in operands.chunks(LANES) {
+            for (&[x, y], sum) in iter::zip(chunk, self.eval_many(chunk)) {
+                let wrong = sum ^ x.wrapping_add(y);
+                for (bit, errors) in errors.iter_mut().enumerate() {
+                    *errors += (wrong >> bit & 1) as usize;
+                }
+            }
+        }
+        errors
+    }
+}
@@ -0,0 +1,106 @@
+use super::{bit, numbered, Gate, Wire};
+use crate::utils::*;
+use std::fmt::Write;
+
+fn gates<'a, 'w>(
+    wires: &'w HashMap<&'a str, Wire<'a>>,
+) -> impl Iterator<Item = (&'a str, Gate, &'a str, &'a str)> + use<'a, 'w> {
+    wires
+        .iter()
+        .filter_map(|(&name, wire)| match *wire {
+            Wire::Out { gate, left, right } => Some((name, gate, left, right)),
+            Wire::In { .. } => None,
+        })
+        .sorted_unstable_by_key(|&(name, ..)| name)
+}
+
+// graphviz, with a node per wire shaped by the gate driving it, and `highlight`ed wires
+// filled in red
+pub fn to_dot(wires: &HashMap<&str, Wire>, highlight: &[&str]) -> String {
+    let mut dot = String::from("digraph circuit {\n    rankdir=LR;\n");
+    let style = |name| {
+        if highlight.contains(&name) {
+            ", style=filled, fillcolor=salmon"
+        } else {
+            ""
+        }
+    };
+    for name in numbered(wires, 'x').into_iter().chain(numbered(wires, 'y')) {
+        writeln!(dot, "    {name} [shape=circle{}];", style(name)).unwrap();
+    }
+    for (name, gate, left, right) in gates(wires) {
+        let (label, shape) = match gate {
+            Gate::And => ("AND", "box"),
+            Gate::Or => ("OR", "ellipse"),
+            Gate::Xor => ("XOR", "diamond"),
+        };
+        let periphery = if name.starts_with('z') {
+            ", peripheries=2"
+        } else {
+            ""
+        };
+        writeln!(
+            dot,
+            "    {name} [label=\"{label}\\n{name}\", shape={shape}{periphery}{}];",
+            style(name)
+        )
+        .unwrap();
+        writeln!(dot, "    {left} -> {name};\n    {right} -> {name};").unwrap();
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+// structural verilog built from gate primitives, with the numbered wires as bus bits
+pub fn to_verilog(wires: &HashMap<&str, Wire>, module: &str) -> String {
+    let net = |name: &str| match (name.chars().next(), bit(name)) {
+        (Some(bus @ ('x' | 'y' | 'z')), Some(bit)) => format!("{bus}[{bit}]"),
+        // prefixed so wires can't collide with keywords like `and` or `or`
+        _ => format!("w_{name}"),
+    };
+    // buses run up to their highest bit, so gaps in the numbering are left unconnected
+    let width = |prefix| {
+        numbered(wires, prefix)
+            .last()
+            .and_then(|name| bit(name))
+            .map_or(0, |bit| bit as usize + 1)
+    };
+
+    let mut verilog = format!("module {module}(\n");
+    writeln!(verilog, "    input [{}:0] x,", width('x').max(1) - 1).unwrap();
+    writeln!(verilog, "    input [{}:0] y,", width('y').max(1) - 1).unwrap();
+    writeln!(verilog, "    output [{}:0] z\n);", width('z').max(1) - 1).unwrap();
+    for (name, ..) in gates(wires).filter(|&(name, ..)| !net(name).contains('[')) {
+        writeln!(verilog, "    wire {};", net(name)).unwrap();
+    }
+    verilog.push('\n');
+    for (i, (name, gate, left, right)) in gates(wires).enumerate() {
+        let primitive = match gate {
+            Gate::And => "and",
+            Gate::Or => "or",
+            Gate::Xor => "xor",
+        };
+        let (name, left, right) = (net(name), net(left), net(right));
+        writeln!(verilog, "    {primitive} g{i} ({name}, {left}, {right});").unwrap();
+    }
+    verilog.push_str("endmodule\n");
+    verilog
+}
+
+// berkeley logic interchange format, with each gate as a cover of its true rows
+pub fn to_blif(wires: &HashMap<&str, Wire>, model: &str) -> String {
+    let mut blif = format!(".model {model}\n");
+    let inputs = numbered(wires, 'x').into_iter().chain(numbered(wires, 'y'));
+    writeln!(blif, ".inputs {}", inputs.format(" ")).unwrap();
+    writeln!(blif, ".outputs {}", numbered(wires, 'z').iter().format(" ")).unwrap();
+    for (name, gate, left, right) in gates(wires) {
+        let cover = match gate {
+            Gate::And => "11 1\n",
+            Gate::Or => "1- 1\n-1 1\n",
+            Gate::Xor => "10 1\n01 1\n",
+        };
+        write!(blif, ".names {left} {right} {name}\n{cover}").unwrap();
+    }
+    blif.push_str(".end\n");
+    blif
+}
@@ -12,11 +12,11 @@ pub fn part1(input: &str) -> Answer {
 
     grid.iter()
         .filter(|&(_, letter)| letter == 'X')
-        .flat_map(|((row, column), _)| {
+        .flat_map(|(index, _)| {
             let grid = &grid;
-            ALL_DIRS.iter().filter(move |&(r, c)| {
+            Dir8::ALL.iter().filter(move |&&dir| {
                 "MAS".chars().zip(1..).all(|(letter, i)| {
-                    grid.get((row + r * i, column + c * i))
+                    grid.get(index + dir.to_offset() * i)
                         .is_some_and(|b| b == letter)
                 })
             })
@@ -15,10 +15,7 @@ const _SAMPLE: &str = "\
 
 fn parse_line(line: &str) -> (u64, ArrayVec<[u64; ARRAY_LEN]>) {
     let (test_val, line) = parse(line);
-    let nums = line[2..]
-        .split_ascii_whitespace()
-        .map(|l| parse(l).0)
-        .collect();
+    let nums = parse_all(line).collect();
     (test_val, nums)
 }
 
@@ -1,5 +1,11 @@
 use aoc_2024::*;
-use std::{env, fs, time::Instant};
+use day17::{
+    asm, decompile,
+    trace::{Breakpoint, Tracer},
+    vm::Program,
+};
+use day24::export;
+use std::{env, error::Error, fs, process, time::Instant};
 use utils::*;
 
 type Puzzle = fn(&str) -> Answer;
@@ -36,7 +42,22 @@ const DAYS: [(Puzzle, Puzzle); 26] = [
 fn main() {
     let get_input = |day| fs::read_to_string(format!("input/day{day}.txt")).unwrap();
 
-    if let Some(arg) = env::args().nth(1) {
+    if let Some(command @ ("asm" | "disasm" | "decompile" | "trace")) =
+        env::args().nth(1).as_deref()
+    {
+        let path = env::args().nth(2).unwrap_or("input/day17.txt".into());
+        let source = fs::read_to_string(path).unwrap();
+        if let Err(err) = day17_tool(command, &source) {
+            eprintln!("{err}");
+            process::exit(1);
+        }
+    } else if let Some(format @ ("dot" | "verilog" | "blif" | "repair")) =
+        env::args().nth(1).as_deref()
+    {
+        let path = env::args().nth(2).unwrap_or("input/day24.txt".into());
+        let source = fs::read_to_string(path).unwrap();
+        print!("{}", day24_export(format, &source));
+    } else if let Some(arg) = env::args().nth(1) {
         let day = arg.parse().expect("Invalid argument, expected a number");
         let input = get_input(day);
         let (part1, part2) = DAYS[day];
@@ -65,5 +86,60 @@ fn run_puzzle(input: &str, puzzle: Puzzle) {
     if answer != Answer::Unfinished {
         print!(" ({:?})", time.elapsed());
     }
+    let memo_stats = MemoStats::take();
+    if memo_stats.lookups() > 0 {
+        print!(" [memo: {memo_stats}]");
+    }
     println!();
 }
+
+const TRACE_LIMIT: usize = 10_000;
+
+// `asm <file>` prints the program for an assembly file, `trace <file>` prints every step
+// of a puzzle input's program, and `disasm <file>` and `decompile <file>` print the
+// assembly or pseudo-code for a puzzle input or a bare comma separated program
+fn day17_tool(command: &str, source: &str) -> Result<(), Box<dyn Error>> {
+    let program = || {
+        let program = source
+            .lines()
+            .find_map(|line| line.strip_prefix("Program:"))
+            .unwrap_or(source);
+        Program::parse(program)
+    };
+
+    match command {
+        "asm" => println!("Program: {}", asm::assemble(source)?.octals().join(",")),
+        "disasm" => print!("{}", asm::disassemble(&program()?)),
+        "decompile" => print!("{}", decompile::decompile(&program()?)?),
+        "trace" => {
+            let (registers, program) = day17::parse_debugger(source);
+            let mut tracer = Tracer::new(program.machine(registers).with_limit(TRACE_LIMIT));
+            // stop once the program has output one more value than its own length
+            tracer.break_at(Breakpoint::Outputs(program.len() * 2 + 1));
+            // the trace is most useful when the run fails, so it's printed either way
+            let stop = tracer.run();
+            print!("{}", tracer.dump());
+            let stop = stop?;
+            println!("{stop:?} with output {}", tracer.outputs().iter().join(","));
+        }
+        _ => unreachable!(),
+    }
+    Ok(())
+}
+
+// `dot <file>`, `verilog <file>` and `blif <file>` print a day24 circuit in that format,
+// with the dot graph highlighting the wires that look swapped, and `repair <file>` prints
+// the swaps that fix it along with how they were checked
+fn day24_export(format: &str, source: &str) -> String {
+    let wires = day24::parse_system(source);
+    match format {
+        "dot" => export::to_dot(&wires, &day24::suspects(&wires)),
+        "verilog" => export::to_verilog(&wires, "adder"),
+        "blif" => export::to_blif(&wires, "adder"),
+        "repair" => match day24::repair(&wires) {
+            Ok(repair) => format!("{repair}\n"),
+            Err(err) => format!("{err}\n"),
+        },
+        _ => unreachable!(),
+    }
+}
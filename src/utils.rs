@@ -1,16 +1,31 @@
 use atoi_simd::Parse;
 use rustc_hash::FxHashMap;
 use std::{
+    array,
+    collections::hash_map,
+    convert::Infallible,
+    error::Error,
     fmt::{self, Debug, Display, Formatter},
     hash::Hash,
     io::{self, Write},
-    mem,
-    ops::{Add, AddAssign, Deref, DerefMut, Sub, SubAssign},
+    iter, mem,
+    ops::{
+        Add, AddAssign, Deref, DerefMut, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub,
+        SubAssign,
+    },
+    str::FromStr,
+    sync::atomic::{self, AtomicU64},
     thread,
     time::Duration,
 };
 
+pub mod linear;
+pub mod scan;
+pub mod search;
+
+pub use crate::scan;
 pub use itertools::Itertools;
+pub use linear::Rational;
 pub use rayon::{
     iter::{
         FromParallelIterator, IndexedParallelIterator, IntoParallelIterator,
@@ -19,12 +34,17 @@ pub use rayon::{
     str::ParallelString,
 };
 pub use rustc_hash::{FxHashMap as HashMap, FxHashSet as HashSet};
+pub use scan::ScanError;
 pub use tinyvec::{array_vec, tiny_vec, ArrayVec, TinyVec};
 
-#[derive(PartialEq)]
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
 pub enum Answer {
-    Number(i64),
+    Number(i128),
+    // only for values that don't fit in `Number`, so equal answers compare equal
+    Unsigned(WideUnsigned),
     String(String),
+    // ascii art, rendered starting on its own line
+    Multiline(String),
     Unfinished,
 }
 
@@ -32,12 +52,36 @@ impl Display for Answer {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             Self::Number(n) => write!(f, "{n}"),
+            Self::Unsigned(n) => write!(f, "{}", n.get()),
             Self::String(s) => write!(f, "{s}"),
+            Self::Multiline(s) => s.lines().try_for_each(|line| write!(f, "\n{line}")),
             Self::Unfinished => write!(f, "[unfinished]"),
         }
     }
 }
 
+impl FromStr for Answer {
+    type Err = Infallible;
+
+    // numbers are only recognised if they display the same way, so `007` or `+7` stay strings
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let number = |n: &dyn Display| n.to_string() == s;
+        Ok(
+            if let Some(n) = s.parse::<i128>().ok().filter(|n| number(n)) {
+                Self::Number(n)
+            } else if let Some(n) = s.parse::<u128>().ok().filter(|n| number(n)) {
+                n.into()
+            } else if s == "[unfinished]" {
+                Self::Unfinished
+            } else if let Some(lines) = s.strip_prefix('\n') {
+                Self::Multiline(lines.into())
+            } else {
+                Self::String(s.into())
+            },
+        )
+    }
+}
+
 impl From<String> for Answer {
     fn from(answer: String) -> Self {
         Self::String(answer)
@@ -50,11 +94,31 @@ impl From<&str> for Answer {
     }
 }
 
+impl From<u128> for Answer {
+    fn from(answer: u128) -> Self {
+        WideUnsigned::new(answer).map_or_else(|| Self::Number(answer as _), Self::Unsigned)
+    }
+}
+
+// a u128 too big for an i128, so no number has two representations
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub struct WideUnsigned(u128);
+
+impl WideUnsigned {
+    pub fn new(n: u128) -> Option<Self> {
+        i128::try_from(n).is_err().then_some(Self(n))
+    }
+
+    pub fn get(self) -> u128 {
+        self.0
+    }
+}
+
 macro_rules! from_int {
     ($int:ty) => {
         impl From<$int> for Answer {
             fn from(answer: $int) -> Self {
-                Self::Number(answer as _)
+                Self::Number(answer.try_into().expect("answer doesn't fit in an i128"))
             }
         }
     };
@@ -65,6 +129,7 @@ from_int!(i32);
 from_int!(u32);
 from_int!(i64);
 from_int!(u64);
+from_int!(i128);
 from_int!(isize);
 from_int!(usize);
 
@@ -72,6 +137,24 @@ pub fn sleep(ms: u64) {
     thread::sleep(Duration::from_millis(ms));
 }
 
+// splitmix64, for reproducible test data rather than anything statistical
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+}
+
 #[derive(PartialEq, Eq, Hash, Copy, Clone, Default, Debug)]
 #[repr(u8)]
 pub enum Dir {
@@ -83,6 +166,8 @@ pub enum Dir {
 }
 
 impl Dir {
+    pub const ALL: [Self; 4] = [Self::North, Self::East, Self::South, Self::West];
+
     pub fn clockwise(self) -> Self {
         match self {
             Self::North => Self::East,
@@ -100,75 +185,453 @@ impl Dir {
             Self::West => Self::South,
         }
     }
+
+    pub fn opposite(self) -> Self {
+        self.clockwise().clockwise()
+    }
+
+    pub fn to_offset(self) -> Point {
+        self.into()
+    }
+}
+
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
+pub enum DirNotation {
+    Arrow,
+    Udlr,
+    Compass,
+}
+
+impl Dir {
+    pub fn to_char(self, notation: DirNotation) -> char {
+        match (notation, self) {
+            (DirNotation::Arrow, _) => self as u8 as char,
+            (DirNotation::Udlr, Self::North) => 'U',
+            (DirNotation::Udlr, Self::East) => 'R',
+            (DirNotation::Udlr, Self::South) => 'D',
+            (DirNotation::Udlr, Self::West) => 'L',
+            (DirNotation::Compass, Self::North) => 'N',
+            (DirNotation::Compass, Self::East) => 'E',
+            (DirNotation::Compass, Self::South) => 'S',
+            (DirNotation::Compass, Self::West) => 'W',
+        }
+    }
+}
+
+impl Display for Dir {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_char(DirNotation::Arrow))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDirError(String);
+
+impl Display for ParseDirError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid direction: {:?}", self.0)
+    }
+}
+
+impl Error for ParseDirError {}
+
+impl TryFrom<u8> for Dir {
+    type Error = ParseDirError;
+
+    fn try_from(byte: u8) -> Result<Self, Self::Error> {
+        match byte {
+            b'^' | b'U' | b'u' | b'N' | b'n' => Ok(Self::North),
+            b'>' | b'R' | b'r' | b'E' | b'e' => Ok(Self::East),
+            b'v' | b'D' | b'd' | b'S' | b's' => Ok(Self::South),
+            b'<' | b'L' | b'l' | b'W' | b'w' => Ok(Self::West),
+            _ => Err(ParseDirError(char::from(byte).into())),
+        }
+    }
+}
+
+impl TryFrom<Byte> for Dir {
+    type Error = ParseDirError;
+
+    fn try_from(byte: Byte) -> Result<Self, Self::Error> {
+        byte.0.try_into()
+    }
+}
+
+impl TryFrom<char> for Dir {
+    type Error = ParseDirError;
+
+    fn try_from(char: char) -> Result<Self, Self::Error> {
+        match char {
+            '↑' => Ok(Self::North),
+            '→' => Ok(Self::East),
+            '↓' => Ok(Self::South),
+            '←' => Ok(Self::West),
+            _ => u8::try_from(char)
+                .map_err(|_| ParseDirError(char.into()))?
+                .try_into(),
+        }
+    }
+}
+
+impl FromStr for Dir {
+    type Err = ParseDirError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(char), None) => char.try_into(),
+            _ => match s.to_ascii_lowercase().as_str() {
+                "up" | "north" => Ok(Self::North),
+                "right" | "east" => Ok(Self::East),
+                "down" | "south" => Ok(Self::South),
+                "left" | "west" => Ok(Self::West),
+                _ => Err(ParseDirError(s.into())),
+            },
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Default, Debug)]
+pub enum Dir8 {
+    #[default]
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
 }
 
-pub type Index = (isize, isize);
+impl Dir8 {
+    pub const ALL: [Self; 8] = [
+        Self::North,
+        Self::NorthEast,
+        Self::East,
+        Self::SouthEast,
+        Self::South,
+        Self::SouthWest,
+        Self::West,
+        Self::NorthWest,
+    ];
+
+    pub fn rotate(self, eighths: isize) -> Self {
+        Self::ALL[(self as isize + eighths).rem_euclid(8) as usize]
+    }
+
+    pub fn clockwise(self) -> Self {
+        self.rotate(1)
+    }
+
+    pub fn counter_clockwise(self) -> Self {
+        self.rotate(-1)
+    }
+
+    pub fn opposite(self) -> Self {
+        self.rotate(4)
+    }
 
-impl Add<Dir> for Index {
-    type Output = Index;
+    pub fn is_diagonal(self) -> bool {
+        self as u8 % 2 == 1
+    }
+
+    pub fn to_offset(self) -> Point {
+        match self {
+            Self::North => Point::new(-1, 0),
+            Self::NorthEast => Point::new(-1, 1),
+            Self::East => Point::new(0, 1),
+            Self::SouthEast => Point::new(1, 1),
+            Self::South => Point::new(1, 0),
+            Self::SouthWest => Point::new(1, -1),
+            Self::West => Point::new(0, -1),
+            Self::NorthWest => Point::new(-1, -1),
+        }
+    }
+}
 
-    fn add(self, dir: Dir) -> Self::Output {
-        let (row, column) = self;
+impl From<Dir> for Dir8 {
+    fn from(dir: Dir) -> Self {
         match dir {
-            Dir::North => (row - 1, column),
-            Dir::East => (row, column + 1),
-            Dir::South => (row + 1, column),
-            Dir::West => (row, column - 1),
+            Dir::North => Self::North,
+            Dir::East => Self::East,
+            Dir::South => Self::South,
+            Dir::West => Self::West,
         }
     }
 }
 
-impl AddAssign<Dir> for Index {
-    fn add_assign(&mut self, dir: Dir) {
-        *self = *self + dir;
+// pointy-topped hexes in axial coordinates, with `row` as r and `column` as q
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Default, Debug)]
+pub enum HexDir {
+    #[default]
+    East,
+    SouthEast,
+    SouthWest,
+    West,
+    NorthWest,
+    NorthEast,
+}
+
+impl HexDir {
+    pub const ALL: [Self; 6] = [
+        Self::East,
+        Self::SouthEast,
+        Self::SouthWest,
+        Self::West,
+        Self::NorthWest,
+        Self::NorthEast,
+    ];
+
+    pub fn rotate(self, sixths: isize) -> Self {
+        Self::ALL[(self as isize + sixths).rem_euclid(6) as usize]
     }
+
+    pub fn clockwise(self) -> Self {
+        self.rotate(1)
+    }
+
+    pub fn counter_clockwise(self) -> Self {
+        self.rotate(-1)
+    }
+
+    pub fn opposite(self) -> Self {
+        self.rotate(3)
+    }
+
+    pub fn to_offset(self) -> Point {
+        match self {
+            Self::East => Point::new(0, 1),
+            Self::SouthEast => Point::new(1, 0),
+            Self::SouthWest => Point::new(1, -1),
+            Self::West => Point::new(0, -1),
+            Self::NorthWest => Point::new(-1, 0),
+            Self::NorthEast => Point::new(-1, 1),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct Point {
+    pub row: isize,
+    pub column: isize,
 }
 
-impl Sub<Dir> for Index {
-    type Output = Index;
+pub type Index = Point;
+
+impl Point {
+    pub const ZERO: Self = Self::new(0, 0);
+
+    pub const fn new(row: isize, column: isize) -> Self {
+        Self { row, column }
+    }
+
+    pub const fn from_xy(x: isize, y: isize) -> Self {
+        Self::new(y, x)
+    }
+
+    pub const fn x(self) -> isize {
+        self.column
+    }
+
+    pub const fn y(self) -> isize {
+        self.row
+    }
+
+    pub const fn to_xy(self) -> (isize, isize) {
+        (self.column, self.row)
+    }
+
+    pub fn manhattan(self, other: Self) -> usize {
+        self.row.abs_diff(other.row) + self.column.abs_diff(other.column)
+    }
 
-    fn sub(self, dir: Dir) -> Self::Output {
-        let (row, column) = self;
+    pub fn chebyshev(self, other: Self) -> usize {
+        self.row
+            .abs_diff(other.row)
+            .max(self.column.abs_diff(other.column))
+    }
+
+    pub fn rotate_90(self) -> Self {
+        Self::new(self.column, -self.row)
+    }
+
+    pub fn rotate_270(self) -> Self {
+        Self::new(-self.column, self.row)
+    }
+
+    pub fn signum(self) -> Self {
+        Self::new(self.row.signum(), self.column.signum())
+    }
+
+    pub fn abs(self) -> Self {
+        Self::new(self.row.abs(), self.column.abs())
+    }
+
+    pub fn dot(self, other: Self) -> isize {
+        self.row * other.row + self.column * other.column
+    }
+
+    pub fn cross(self, other: Self) -> isize {
+        self.row * other.column - self.column * other.row
+    }
+
+    pub fn within(self, bounds: Self) -> bool {
+        (0..bounds.row).contains(&self.row) && (0..bounds.column).contains(&self.column)
+    }
+
+    pub fn hex_distance(self, other: Self) -> usize {
+        let Point { row, column } = self - other;
+        (row.unsigned_abs() + column.unsigned_abs() + (row + column).unsigned_abs()) / 2
+    }
+
+    pub fn min_each(self, other: Self) -> Self {
+        Self::new(self.row.min(other.row), self.column.min(other.column))
+    }
+
+    pub fn max_each(self, other: Self) -> Self {
+        Self::new(self.row.max(other.row), self.column.max(other.column))
+    }
+}
+
+impl Debug for Point {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&(self.row, self.column), f)
+    }
+}
+
+impl Display for Point {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.row, self.column)
+    }
+}
+
+impl From<(isize, isize)> for Point {
+    fn from((row, column): (isize, isize)) -> Self {
+        Self::new(row, column)
+    }
+}
+
+impl From<Point> for (isize, isize) {
+    fn from(Point { row, column }: Point) -> Self {
+        (row, column)
+    }
+}
+
+impl From<Dir> for Point {
+    fn from(dir: Dir) -> Self {
         match dir {
-            Dir::North => (row + 1, column),
-            Dir::East => (row, column - 1),
-            Dir::South => (row - 1, column),
-            Dir::West => (row, column + 1),
+            Dir::North => Self::new(-1, 0),
+            Dir::East => Self::new(0, 1),
+            Dir::South => Self::new(1, 0),
+            Dir::West => Self::new(0, -1),
         }
     }
 }
 
-impl SubAssign<Dir> for Index {
-    fn sub_assign(&mut self, dir: Dir) {
-        *self = *self - dir;
+impl From<Dir8> for Point {
+    fn from(dir: Dir8) -> Self {
+        dir.to_offset()
     }
 }
 
-pub const DIRS: [Index; 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+impl From<HexDir> for Point {
+    fn from(dir: HexDir) -> Self {
+        dir.to_offset()
+    }
+}
 
-pub fn dirs((row, column): Index) -> [Index; DIRS.len()] {
-    DIRS.map(|(r, c)| (row + r, column + c))
+macro_rules! point_op {
+    ($op:ident::$f:ident, $op_assign:ident::$f_assign:ident, $rhs:ty) => {
+        impl $op<$rhs> for Point {
+            type Output = Point;
+
+            fn $f(self, rhs: $rhs) -> Self::Output {
+                let rhs = Point::from(rhs);
+                Point::new(self.row.$f(rhs.row), self.column.$f(rhs.column))
+            }
+        }
+
+        impl $op_assign<$rhs> for Point {
+            fn $f_assign(&mut self, rhs: $rhs) {
+                *self = (*self).$f(rhs);
+            }
+        }
+    };
 }
+point_op!(Add::add, AddAssign::add_assign, Point);
+point_op!(Sub::sub, SubAssign::sub_assign, Point);
+point_op!(Add::add, AddAssign::add_assign, Dir);
+point_op!(Sub::sub, SubAssign::sub_assign, Dir);
+point_op!(Add::add, AddAssign::add_assign, Dir8);
+point_op!(Sub::sub, SubAssign::sub_assign, Dir8);
+point_op!(Add::add, AddAssign::add_assign, HexDir);
+point_op!(Sub::sub, SubAssign::sub_assign, HexDir);
+
+macro_rules! point_scalar_op {
+    ($op:ident::$f:ident, $op_assign:ident::$f_assign:ident) => {
+        impl $op<isize> for Point {
+            type Output = Point;
+
+            fn $f(self, rhs: isize) -> Self::Output {
+                Point::new(self.row.$f(rhs), self.column.$f(rhs))
+            }
+        }
 
-pub const DIAGS: [Index; 4] = [(-1, -1), (-1, 1), (1, -1), (1, 1)];
+        impl $op_assign<isize> for Point {
+            fn $f_assign(&mut self, rhs: isize) {
+                *self = (*self).$f(rhs);
+            }
+        }
+    };
+}
+point_scalar_op!(Mul::mul, MulAssign::mul_assign);
+point_scalar_op!(Div::div, DivAssign::div_assign);
+point_scalar_op!(Rem::rem, RemAssign::rem_assign);
 
-pub fn diags((row, column): Index) -> [Index; DIAGS.len()] {
-    DIAGS.map(|(r, c)| (row + r, column + c))
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Self::Output {
+        Point::new(-self.row, -self.column)
+    }
+}
+
+pub const DIRS: [Index; 4] = [
+    Point::new(-1, 0),
+    Point::new(0, 1),
+    Point::new(1, 0),
+    Point::new(0, -1),
+];
+
+pub fn dirs(index: Index) -> [Index; DIRS.len()] {
+    DIRS.map(|delta| index + delta)
+}
+
+pub const DIAGS: [Index; 4] = [
+    Point::new(-1, -1),
+    Point::new(-1, 1),
+    Point::new(1, -1),
+    Point::new(1, 1),
+];
+
+pub fn diags(index: Index) -> [Index; DIAGS.len()] {
+    DIAGS.map(|delta| index + delta)
 }
 
 pub const ALL_DIRS: [Index; 8] = [
-    (-1, -1),
-    (-1, 0),
-    (-1, 1),
-    (0, -1),
-    (0, 1),
-    (1, -1),
-    (1, 0),
-    (1, 1),
+    Point::new(-1, -1),
+    Point::new(-1, 0),
+    Point::new(-1, 1),
+    Point::new(0, -1),
+    Point::new(0, 1),
+    Point::new(1, -1),
+    Point::new(1, 0),
+    Point::new(1, 1),
 ];
 
-pub fn all_dirs((row, column): Index) -> [Index; ALL_DIRS.len()] {
-    ALL_DIRS.map(|(r, c)| (row + r, column + c))
+pub fn all_dirs(index: Index) -> [Index; ALL_DIRS.len()] {
+    ALL_DIRS.map(|delta| index + delta)
 }
 
 #[derive(Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
@@ -248,7 +711,7 @@ impl<'a> Grid<'a> {
     }
 
     pub fn bounds(&self) -> Index {
-        (self.0.len() as _, self.0[0].len() as _)
+        Point::new(self.0.len() as _, self.0[0].len() as _)
     }
 
     pub fn iter(&self) -> impl Iterator<Item = (Index, Byte)> + use<'_> {
@@ -256,7 +719,7 @@ impl<'a> Grid<'a> {
             (0..self.0.len()).flat_map(move |row| {
                 (0..self.0.get_unchecked(row).len()).map(move |column| {
                     (
-                        (row as _, column as _),
+                        Point::new(row as _, column as _),
                         *self.0.get_unchecked(row).get_unchecked(column),
                     )
                 })
@@ -264,7 +727,7 @@ impl<'a> Grid<'a> {
         }
     }
 
-    pub fn get(&self, (row, column): Index) -> Option<Byte> {
+    pub fn get(&self, Point { row, column }: Index) -> Option<Byte> {
         self.0
             .get(row as usize)
             .and_then(|row| row.get(column as usize))
@@ -273,11 +736,11 @@ impl<'a> Grid<'a> {
 
     pub fn print_with(&self, mut f: impl FnMut(Index) -> Option<char>) {
         let mut stdout = io::stdout().lock();
-        for ((row, column), byte) in self.iter() {
-            if row != 0 && column == 0 {
+        for (index, byte) in self.iter() {
+            if index.row != 0 && index.column == 0 {
                 stdout.write_all(b"\n").unwrap();
             }
-            let char = f((row, column)).unwrap_or(byte.into());
+            let char = f(index).unwrap_or(byte.into());
             write!(&mut stdout, "{char}").unwrap();
         }
         stdout.write_all(b"\n").unwrap();
@@ -306,7 +769,12 @@ impl GridOwned {
         }
     }
 
-    pub fn new_dims((rows, cols): Index) -> Self {
+    pub fn new_dims(
+        Point {
+            row: rows,
+            column: cols,
+        }: Index,
+    ) -> Self {
         Self {
             buf: vec![Byte(b' '); rows as usize * cols as usize].into(),
             columns: cols as _,
@@ -314,13 +782,17 @@ impl GridOwned {
     }
 
     pub fn bounds(&self) -> Index {
-        let rows = self.buf.len() / self.columns;
-        (rows as _, self.columns as _)
+        // a grid with no columns has no rows either
+        let rows = self.buf.len().checked_div(self.columns).unwrap_or(0);
+        Point::new(rows as _, self.columns as _)
     }
 
     pub fn indices(&self) -> impl Iterator<Item = Index> + use<> {
-        let (rows, cols) = self.bounds();
-        (0..rows).cartesian_product(0..cols)
+        let Point {
+            row: rows,
+            column: cols,
+        } = self.bounds();
+        (0..rows).cartesian_product(0..cols).map(Point::from)
     }
 
     pub fn iter(&self) -> impl Iterator<Item = (Index, Byte)> + use<'_> {
@@ -328,13 +800,10 @@ impl GridOwned {
     }
 
     pub fn iter_mut(&mut self) -> impl Iterator<Item = (Index, &mut Byte)> + use<'_> {
-        let (rows, cols) = self.bounds();
-        (0..rows)
-            .cartesian_product(0..cols)
-            .zip(self.buf.iter_mut())
+        self.indices().zip(self.buf.iter_mut())
     }
 
-    pub fn get(&self, (row, column): Index) -> Option<Byte> {
+    pub fn get(&self, Point { row, column }: Index) -> Option<Byte> {
         let columns = self.columns as isize;
         (row >= 0 && (0..columns).contains(&column))
             .then(|| self.buf.get((row * columns + column) as usize))
@@ -342,7 +811,7 @@ impl GridOwned {
             .copied()
     }
 
-    pub fn get_mut(&mut self, (row, column): Index) -> Option<&mut Byte> {
+    pub fn get_mut(&mut self, Point { row, column }: Index) -> Option<&mut Byte> {
         let columns = self.columns as isize;
         (row >= 0 && (0..columns).contains(&column))
             .then(|| self.buf.get_mut((row * columns + column) as usize))
@@ -355,11 +824,11 @@ impl GridOwned {
 
     pub fn print_with(&self, mut f: impl FnMut(Index) -> Option<char>) {
         let mut stdout = io::stdout().lock();
-        for ((row, column), byte) in self.iter() {
-            if row != 0 && column == 0 {
+        for (index, byte) in self.iter() {
+            if index.row != 0 && index.column == 0 {
                 stdout.write_all(b"\n").unwrap();
             }
-            let char = f((row, column)).unwrap_or(byte.into());
+            let char = f(index).unwrap_or(byte.into());
             write!(&mut stdout, "{char}").unwrap();
         }
         stdout.write_all(b"\n").unwrap();
@@ -367,9 +836,413 @@ impl GridOwned {
     }
 }
 
+// `None` when the bounds are empty, since nothing can wrap into them
+pub fn wrap(index: Index, bounds: Index) -> Option<Index> {
+    Some(Point::new(
+        index.row.checked_rem_euclid(bounds.row)?,
+        index.column.checked_rem_euclid(bounds.column)?,
+    ))
+}
+
+pub fn wrapping_add(index: Index, delta: Index, bounds: Index) -> Option<Index> {
+    wrap(index + delta, bounds)
+}
+
+pub fn flat_index(index: Index, bounds: Index) -> Option<usize> {
+    index
+        .within(bounds)
+        .then(|| (index.row * bounds.column + index.column) as _)
+}
+
+pub fn unflat_index(flat: usize, bounds: Index) -> Option<Index> {
+    let flat = flat as isize;
+    Some(Point::new(
+        flat.checked_div(bounds.column)?,
+        flat.checked_rem(bounds.column)?,
+    ))
+}
+
+#[derive(Clone)]
+pub struct TorusGrid(GridOwned);
+
+impl TorusGrid {
+    pub fn new(input: &str) -> Self {
+        Self(GridOwned::new(input))
+    }
+
+    pub fn new_dims(bounds: Index) -> Self {
+        Self(GridOwned::new_dims(bounds))
+    }
+
+    pub fn bounds(&self) -> Index {
+        self.0.bounds()
+    }
+
+    pub fn wrap(&self, index: Index) -> Option<Index> {
+        wrap(index, self.bounds())
+    }
+
+    pub fn add(&self, index: Index, delta: Index) -> Option<Index> {
+        wrapping_add(index, delta, self.bounds())
+    }
+
+    pub fn indices(&self) -> impl Iterator<Item = Index> + use<> {
+        self.0.indices()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Index, Byte)> + use<'_> {
+        self.0.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Index, &mut Byte)> + use<'_> {
+        self.0.iter_mut()
+    }
+
+    // every index wraps onto a cell, unless the grid is empty
+    pub fn get(&self, index: Index) -> Option<Byte> {
+        self.0.get(self.wrap(index)?)
+    }
+
+    pub fn get_mut(&mut self, index: Index) -> Option<&mut Byte> {
+        let index = self.wrap(index)?;
+        self.0.get_mut(index)
+    }
+
+    pub fn set(&mut self, index: Index, char: char) {
+        *self.get_mut(index).unwrap() = (char as u8).into();
+    }
+
+    pub fn dirs(&self, index: Index) -> Option<[Index; DIRS.len()]> {
+        let bounds = self.bounds();
+        self.wrap(index)?;
+        Some(DIRS.map(|delta| wrapping_add(index, delta, bounds).unwrap()))
+    }
+
+    pub fn all_dirs(&self, index: Index) -> Option<[Index; ALL_DIRS.len()]> {
+        let bounds = self.bounds();
+        self.wrap(index)?;
+        Some(ALL_DIRS.map(|delta| wrapping_add(index, delta, bounds).unwrap()))
+    }
+
+    pub fn print_with(&self, f: impl FnMut(Index) -> Option<char>) {
+        self.0.print_with(f);
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SparseGrid<T> {
+    cells: FxHashMap<Index, T>,
+    min: Index,
+    max: Index,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self {
+            cells: Default::default(),
+            min: Point::new(isize::MAX, isize::MAX),
+            max: Point::new(isize::MIN, isize::MIN),
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    // inclusive top-left and bottom-right corners of every occupied cell
+    pub fn bounding_box(&self) -> Option<(Index, Index)> {
+        (!self.is_empty()).then_some((self.min, self.max))
+    }
+
+    pub fn bounds(&self) -> Index {
+        self.bounding_box()
+            .map_or(Point::ZERO, |(min, max)| max - min + Point::new(1, 1))
+    }
+
+    pub fn contains(&self, index: Index) -> bool {
+        self.cells.contains_key(&index)
+    }
+
+    pub fn get(&self, index: Index) -> Option<&T> {
+        self.cells.get(&index)
+    }
+
+    pub fn get_mut(&mut self, index: Index) -> Option<&mut T> {
+        self.cells.get_mut(&index)
+    }
+
+    pub fn insert(&mut self, index: Index, value: T) -> Option<T> {
+        self.min = self.min.min_each(index);
+        self.max = self.max.max_each(index);
+        self.cells.insert(index, value)
+    }
+
+    pub fn remove(&mut self, index: Index) -> Option<T> {
+        let removed = self.cells.remove(&index)?;
+        let (min, max) = (self.min, self.max);
+        if [min.row, max.row].contains(&index.row)
+            || [min.column, max.column].contains(&index.column)
+        {
+            self.recompute_bounds();
+        }
+        Some(removed)
+    }
+
+    fn recompute_bounds(&mut self) {
+        let Self { min, max, .. } = Self::default();
+        (self.min, self.max) = self.cells.keys().fold((min, max), |(min, max), &index| {
+            (min.min_each(index), max.max_each(index))
+        });
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Index, &T)> + use<'_, T> {
+        self.cells.iter().map(|(&index, value)| (index, value))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Index, &mut T)> + use<'_, T> {
+        self.cells.iter_mut().map(|(&index, value)| (index, value))
+    }
+
+    pub fn print_with(&self, mut f: impl FnMut(Index, Option<&T>) -> char) {
+        let Some((min, max)) = self.bounding_box() else {
+            return;
+        };
+        let mut stdout = io::stdout().lock();
+        for row in min.row..=max.row {
+            for column in min.column..=max.column {
+                let index = Point::new(row, column);
+                write!(&mut stdout, "{}", f(index, self.get(index))).unwrap();
+            }
+            stdout.write_all(b"\n").unwrap();
+        }
+        stdout.flush().unwrap();
+    }
+}
+
+impl SparseGrid<Byte> {
+    // keeps every cell that isn't `blank`, at the same index it had in the grid
+    pub fn from_grid(grid: &GridOwned, blank: char) -> Self {
+        grid.iter().filter(|&(_, byte)| byte != blank).collect()
+    }
+
+    // the bounding box is moved to the origin, and empty cells are filled with `blank`, so
+    // an empty sparse grid gives a 0x0 grid
+    pub fn to_grid(&self, blank: char) -> GridOwned {
+        let mut grid = GridOwned::new_dims(self.bounds());
+        for (_, byte) in grid.iter_mut() {
+            *byte = (blank as u8).into();
+        }
+        for (index, &byte) in self.iter() {
+            *grid.get_mut(index - self.min).unwrap() = byte;
+        }
+        grid
+    }
+}
+
+impl From<&GridOwned> for SparseGrid<Byte> {
+    fn from(grid: &GridOwned) -> Self {
+        grid.iter().collect()
+    }
+}
+
+impl<T> Extend<(Index, T)> for SparseGrid<T> {
+    fn extend<I: IntoIterator<Item = (Index, T)>>(&mut self, iter: I) {
+        for (index, value) in iter {
+            self.insert(index, value);
+        }
+    }
+}
+
+impl<T> FromIterator<(Index, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Index, T)>>(iter: I) -> Self {
+        let mut grid = Self::default();
+        grid.extend(iter);
+        grid
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct UnionFind {
+    parents: Vec<usize>,
+    ranks: Vec<u8>,
+    sizes: Vec<usize>,
+    components: usize,
+}
+
+impl UnionFind {
+    pub fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len).collect(),
+            ranks: vec![0; len],
+            sizes: vec![1; len],
+            components: len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    pub fn components(&self) -> usize {
+        self.components
+    }
+
+    pub fn find(&mut self, mut key: usize) -> usize {
+        let mut root = key;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+        while key != root {
+            key = mem::replace(&mut self.parents[key], root);
+        }
+        root
+    }
+
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.ranks[a] < self.ranks[b] {
+            mem::swap(&mut a, &mut b);
+        }
+        if self.ranks[a] == self.ranks[b] {
+            self.ranks[a] += 1;
+        }
+        self.parents[b] = a;
+        self.sizes[a] += self.sizes[b];
+        self.components -= 1;
+        true
+    }
+
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    pub fn size(&mut self, key: usize) -> usize {
+        let root = self.find(key);
+        self.sizes[root]
+    }
+
+    pub fn roots(&self) -> impl Iterator<Item = usize> + use<'_> {
+        (0..self.len()).filter(|&key| self.parents[key] == key)
+    }
+}
+
+static MEMO_HITS: AtomicU64 = AtomicU64::new(0);
+static MEMO_MISSES: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub struct MemoStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl MemoStats {
+    // collects the stats of every `Memo` dropped since the last call
+    pub fn take() -> Self {
+        Self {
+            hits: MEMO_HITS.swap(0, atomic::Ordering::Relaxed),
+            misses: MEMO_MISSES.swap(0, atomic::Ordering::Relaxed),
+        }
+    }
+
+    pub fn lookups(&self) -> u64 {
+        self.hits + self.misses
+    }
+}
+
+impl Display for MemoStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} hits, {} misses", self.hits, self.misses)
+    }
+}
+
+#[derive(Debug)]
+pub struct Memo<K, V> {
+    cache: FxHashMap<K, V>,
+    capacity: Option<usize>,
+    stats: MemoStats,
+}
+
+impl<K, V> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self {
+            cache: Default::default(),
+            capacity: None,
+            stats: Default::default(),
+        }
+    }
+}
+
+impl<K: Eq + Hash, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // once `capacity` results are cached, new results are still computed but no longer stored
+    pub fn with_capacity_limit(capacity: usize) -> Self {
+        let mut memo = Self::default();
+        memo.capacity = Some(capacity);
+        memo
+    }
+
+    pub fn get_or_compute(&mut self, key: K, compute: impl FnOnce(&mut Self) -> V) -> V {
+        if let Some(value) = self.cache.get(&key) {
+            self.stats.hits += 1;
+            return value.clone();
+        }
+        self.stats.misses += 1;
+        let value = compute(self);
+        if self
+            .capacity
+            .is_none_or(|capacity| self.cache.len() < capacity)
+        {
+            self.cache.insert(key, value.clone());
+        }
+        value
+    }
+
+    pub fn stats(&self) -> MemoStats {
+        self.stats
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+}
+
+impl<K, V> Drop for Memo<K, V> {
+    fn drop(&mut self) {
+        MEMO_HITS.fetch_add(self.stats.hits, atomic::Ordering::Relaxed);
+        MEMO_MISSES.fetch_add(self.stats.misses, atomic::Ordering::Relaxed);
+    }
+}
+
 #[derive(Debug)]
 pub struct Counter<K>(FxHashMap<K, u64>);
 
+pub type CounterIter<'a, K> =
+    iter::Map<hash_map::Iter<'a, K, u64>, fn((&'a K, &'a u64)) -> (&'a K, u64)>;
+
 impl<K> Default for Counter<K> {
     fn default() -> Self {
         Self(Default::default())
@@ -384,6 +1257,64 @@ impl<K: Eq + Hash> Counter<K> {
     pub fn add_n(&mut self, key: K, n: u64) {
         *self.0.entry(key).or_default() += n;
     }
+
+    // takes precedence over the map's `get` through `Deref`, so missing keys count as zero
+    pub fn get(&self, key: &K) -> u64 {
+        self.0.get(key).copied().unwrap_or(0)
+    }
+
+    pub fn total(&self) -> u64 {
+        self.0.values().sum()
+    }
+
+    pub fn most_common(&self, n: usize) -> Vec<(&K, u64)> {
+        self.0
+            .iter()
+            .map(|(key, &count)| (key, count))
+            .k_largest_by_key(n, |&(_, count)| count)
+            .collect()
+    }
+
+    pub fn merge(&mut self, other: Self) {
+        for (key, count) in other.0 {
+            self.add_n(key, count);
+        }
+    }
+
+    // counts can't go negative, so keys that drop to zero are removed
+    pub fn subtract(&mut self, other: Self) {
+        for (key, count) in other.0 {
+            if let Some(current) = self.0.get_mut(&key) {
+                *current = current.saturating_sub(count);
+                if *current == 0 {
+                    self.0.remove(&key);
+                }
+            }
+        }
+    }
+
+    pub fn iter(&self) -> CounterIter<'_, K> {
+        self.0.iter().map(|(key, &count)| (key, count))
+    }
+
+    pub fn iter_sorted(&self) -> impl Iterator<Item = (&K, u64)> + use<'_, K>
+    where
+        K: Ord,
+    {
+        self.iter().sorted_unstable_by(|(a, _), (b, _)| a.cmp(b))
+    }
+}
+
+impl<K: Eq + Hash> AddAssign for Counter<K> {
+    fn add_assign(&mut self, other: Self) {
+        self.merge(other);
+    }
+}
+
+impl<K: Eq + Hash> SubAssign for Counter<K> {
+    fn sub_assign(&mut self, other: Self) {
+        self.subtract(other);
+    }
 }
 
 impl<K: Eq + Hash> Extend<K> for Counter<K> {
@@ -410,15 +1341,31 @@ impl<K: Eq + Hash + Send + Sync> FromParallelIterator<K> for Counter<K> {
                 counter.add(key);
                 counter
             })
-            .reduce(Self::default, |mut acc, counter| {
-                for (key, count) in counter.0 {
-                    *acc.0.entry(key).or_default() += count;
-                }
-                acc
+            .reduce(Self::default, |mut left, right| {
+                left += right;
+                left
             })
     }
 }
 
+impl<K> IntoIterator for Counter<K> {
+    type Item = (K, u64);
+    type IntoIter = <FxHashMap<K, u64> as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a, K: Eq + Hash> IntoIterator for &'a Counter<K> {
+    type Item = (&'a K, u64);
+    type IntoIter = CounterIter<'a, K>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
 impl<K> Deref for Counter<K> {
     type Target = FxHashMap<K, u64>;
 
@@ -427,9 +1374,53 @@ impl<K> Deref for Counter<K> {
     }
 }
 
+pub fn try_parse<T: Parse>(s: &str) -> Option<(T, &str)> {
+    let (t, bytes) = atoi_simd::parse_any(s.as_bytes()).ok()?;
+    Some((t, unsafe { s.get_unchecked(bytes..) }))
+}
+
 pub fn parse<T: Parse>(s: &str) -> (T, &str) {
-    let (t, bytes) = atoi_simd::parse_any(s.as_bytes()).unwrap();
-    (t, unsafe { s.get_unchecked(bytes..) })
+    try_parse(s).unwrap_or_else(|| panic!("expected a number at the start of {s:?}"))
+}
+
+// every integer token in `s`, where a `-` right before the digits makes it negative, or the
+// token itself if it doesn't fit in `T`; for unsigned types, or after another digit like in
+// `10-20`, a `-` is only a separator
+fn tokens<T: Parse>(s: &str) -> impl Iterator<Item = Result<T, &str>> + use<'_, T> {
+    let signed = atoi_simd::parse::<T>(b"-1").is_ok();
+    let bytes = s.as_bytes();
+    let mut pos = 0;
+    iter::from_fn(move || {
+        let start = pos + bytes[pos..].iter().position(u8::is_ascii_digit)?;
+        let end = bytes[start..]
+            .iter()
+            .position(|b| !b.is_ascii_digit())
+            .map_or(bytes.len(), |len| start + len);
+        let negative = signed
+            && start > 0
+            && bytes[start - 1] == b'-'
+            && (start < 2 || !bytes[start - 2].is_ascii_digit());
+        let token = &s[start - negative as usize..end];
+        pos = end;
+        Some(atoi_simd::parse(token.as_bytes()).map_err(|_| token))
+    })
+}
+
+// every integer in `s`, as split up by `tokens`, panicking on any that doesn't fit in `T`
+pub fn parse_all<T: Parse>(s: &str) -> impl Iterator<Item = T> + use<'_, T> {
+    tokens(s).map(|token| {
+        token.unwrap_or_else(|token| {
+            panic!("{token:?} doesn't fit in {}", std::any::type_name::<T>())
+        })
+    })
+}
+
+// exactly `N` integers from `s`, as found by `parse_all`, or `None` if any doesn't fit in `T`
+pub fn parse_n<T: Parse, const N: usize>(s: &str) -> Option<[T; N]> {
+    let mut numbers = tokens(s);
+    let array: [Option<T>; N] = array::from_fn(|_| numbers.next()?.ok());
+    (array.iter().all(Option::is_some) && numbers.next().is_none())
+        .then(|| array.map(|t| unsafe { t.unwrap_unchecked() }))
 }
 
 pub fn digits(mut num: u64) -> u32 {
@@ -445,31 +1436,153 @@ pub fn digits(mut num: u64) -> u32 {
     }
 }
 
-macro_rules! gcd_impl {
-    ($f:ident($t:ty)) => {
-        pub fn $f(a: $t, b: $t) -> $t {
-            let (mut a, mut b) = (a.abs_diff(0), b.abs_diff(0));
-            let mut d = 0;
-            loop {
-                match (a % 2, b % 2) {
-                    (0, 0) => {
-                        a /= 2;
-                        b /= 2;
-                        d += 1;
-                    }
-                    (0, 1) => a /= 2,
-                    (1, 0) => b /= 2,
-                    (1, 1) if a > b => a -= b,
-                    (1, 1) if a < b => b -= a,
-                    _ => break (a as u64 * 2u64.pow(d)) as _,
-                }
+pub trait Integer: Copy + Ord + Default + Debug + Display + Hash + TryFrom<i128> {
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn to_i128(self) -> i128;
+
+    fn from_i128(n: i128) -> Option<Self> {
+        n.try_into().ok()
+    }
+}
+
+pub trait Signed: Integer {}
+
+macro_rules! integer_impl {
+    ($($int:ty),*) => {$(
+        impl Integer for $int {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn to_i128(self) -> i128 {
+                self as _
             }
         }
-    };
+    )*};
+}
+integer_impl!(i8, u8, i16, u16, i32, u32, i64, u64, i128, isize, usize);
+
+macro_rules! signed_impl {
+    ($($int:ty),*) => {$(
+        impl Signed for $int {}
+    )*};
+}
+signed_impl!(i8, i16, i32, i64, i128, isize);
+
+pub fn checked_gcd<T: Integer>(a: T, b: T) -> Option<T> {
+    let (mut a, mut b) = (a.to_i128().unsigned_abs(), b.to_i128().unsigned_abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    T::from_i128(a.try_into().ok()?)
+}
+
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    checked_gcd(a, b).expect("gcd overflowed")
+}
+
+pub fn checked_lcm<T: Integer>(a: T, b: T) -> Option<T> {
+    if a == T::ZERO || b == T::ZERO {
+        return Some(T::ZERO);
+    }
+    let gcd = checked_gcd(a, b)?.to_i128();
+    T::from_i128(
+        (a.to_i128() / gcd)
+            .checked_mul(b.to_i128())?
+            .checked_abs()?,
+    )
+}
+
+pub fn lcm<T: Integer>(a: T, b: T) -> T {
+    checked_lcm(a, b).expect("lcm overflowed")
+}
+
+// returns `(gcd, x, y)` such that `a * x + b * y == gcd`
+pub fn extended_gcd<T: Signed>(a: T, b: T) -> (T, T, T) {
+    let (gcd, x, y) = extended_gcd_i128(a.to_i128(), b.to_i128());
+    let convert = |n| T::from_i128(n).expect("extended_gcd overflowed");
+    (convert(gcd), convert(x), convert(y))
+}
+
+fn extended_gcd_i128(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+pub fn mod_inverse<T: Integer>(a: T, modulus: T) -> Option<T> {
+    let modulus = modulus.to_i128();
+    if modulus <= 0 {
+        return None;
+    }
+    let (gcd, x, _) = extended_gcd_i128(a.to_i128().rem_euclid(modulus), modulus);
+    (gcd == 1)
+        .then(|| T::from_i128(x.rem_euclid(modulus)))
+        .flatten()
+}
+
+pub fn checked_mod_pow<T: Integer>(base: T, mut exp: u64, modulus: T) -> Option<T> {
+    let modulus = modulus.to_i128();
+    if modulus <= 0 {
+        return None;
+    }
+    let mut base = base.to_i128().rem_euclid(modulus);
+    let mut result = 1 % modulus;
+    while exp > 0 {
+        if exp % 2 == 1 {
+            result = result.checked_mul(base)? % modulus;
+        }
+        base = base.checked_mul(base)? % modulus;
+        exp /= 2;
+    }
+    T::from_i128(result)
+}
+
+pub fn mod_pow<T: Integer>(base: T, exp: u64, modulus: T) -> T {
+    checked_mod_pow(base, exp, modulus).expect("mod_pow overflowed or had a non-positive modulus")
+}
+
+// solves `x ≡ residue (mod modulus)` for every pair, returning the smallest non-negative `x`
+// and the combined modulus; the moduli don't need to be coprime
+pub fn crt<T: Integer>(congruences: impl IntoIterator<Item = (T, T)>) -> Option<(T, T)> {
+    let (mut x, mut m) = (0i128, 1i128);
+    for (residue, modulus) in congruences {
+        let modulus = modulus.to_i128();
+        if modulus <= 0 {
+            return None;
+        }
+        let residue = residue.to_i128().rem_euclid(modulus);
+        let (gcd, p, _) = extended_gcd_i128(m, modulus);
+        let diff = residue - x;
+        if diff % gcd != 0 {
+            return None;
+        }
+        let step = modulus / gcd;
+        let k = (diff / gcd % step).checked_mul(p % step)?.rem_euclid(step);
+        x = m.checked_mul(k)?.checked_add(x)?;
+        m = m.checked_mul(step)?;
+        x = x.rem_euclid(m);
+    }
+    Some((T::from_i128(x)?, T::from_i128(m)?))
+}
+
+pub fn checked_isqrt<T: Integer>(n: T) -> Option<T> {
+    let n = n.to_i128();
+    (n >= 0).then(|| T::from_i128(n.isqrt())).flatten()
+}
+
+pub fn isqrt<T: Integer>(n: T) -> T {
+    checked_isqrt(n).expect("isqrt of a negative number")
 }
-gcd_impl!(gcd_i32(i32));
-gcd_impl!(gcd_u32(u32));
-gcd_impl!(gcd_i64(i64));
-gcd_impl!(gcd_u64(u64));
-gcd_impl!(gcd_isize(isize));
-gcd_impl!(gcd_usize(usize));
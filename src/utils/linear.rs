@@ -0,0 +1,237 @@
+use super::gcd;
+use std::{
+    cmp::Ordering,
+    fmt::{self, Display, Formatter},
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Rational {
+    numer: i128,
+    denom: i128,
+}
+
+impl Rational {
+    pub const ZERO: Self = Self { numer: 0, denom: 1 };
+    pub const ONE: Self = Self { numer: 1, denom: 1 };
+
+    pub fn new(numer: i128, denom: i128) -> Self {
+        assert!(denom != 0, "rational with a zero denominator");
+        let gcd = gcd(numer, denom).max(1) * denom.signum();
+        Self {
+            numer: numer / gcd,
+            denom: denom / gcd,
+        }
+    }
+
+    pub fn numer(self) -> i128 {
+        self.numer
+    }
+
+    pub fn denom(self) -> i128 {
+        self.denom
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.numer == 0
+    }
+
+    pub fn is_integer(self) -> bool {
+        self.denom == 1
+    }
+
+    pub fn to_integer(self) -> Option<i128> {
+        self.is_integer().then_some(self.numer)
+    }
+
+    pub fn floor(self) -> i128 {
+        self.numer.div_euclid(self.denom)
+    }
+
+    pub fn ceil(self) -> i128 {
+        -(-self).floor()
+    }
+
+    pub fn recip(self) -> Self {
+        Self::new(self.denom, self.numer)
+    }
+}
+
+impl Default for Rational {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.numer)
+        } else {
+            write!(f, "{}/{}", self.numer, self.denom)
+        }
+    }
+}
+
+macro_rules! from_int {
+    ($($int:ty),*) => {$(
+        impl From<$int> for Rational {
+            fn from(n: $int) -> Self {
+                Self {
+                    numer: n as _,
+                    denom: 1,
+                }
+            }
+        }
+    )*};
+}
+from_int!(i8, u8, i16, u16, i32, u32, i64, u64, i128, isize, usize);
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.numer * other.denom).cmp(&(other.numer * self.denom))
+    }
+}
+
+impl Neg for Rational {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            numer: -self.numer,
+            denom: self.denom,
+        }
+    }
+}
+
+impl Add for Rational {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(
+            self.numer * rhs.denom + rhs.numer * self.denom,
+            self.denom * rhs.denom,
+        )
+    }
+}
+
+impl Sub for Rational {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl Mul for Rational {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self::new(self.numer * rhs.numer, self.denom * rhs.denom)
+    }
+}
+
+impl Div for Rational {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        Self::new(self.numer * rhs.denom, self.denom * rhs.numer)
+    }
+}
+
+macro_rules! assign_op {
+    ($op_assign:ident::$f_assign:ident, $f:ident) => {
+        impl $op_assign for Rational {
+            fn $f_assign(&mut self, rhs: Self) {
+                *self = (*self).$f(rhs);
+            }
+        }
+    };
+}
+assign_op!(AddAssign::add_assign, add);
+assign_op!(SubAssign::sub_assign, sub);
+assign_op!(MulAssign::mul_assign, mul);
+assign_op!(DivAssign::div_assign, div);
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Solution {
+    Unique(Vec<Rational>),
+    None,
+    // every solution is `particular` plus any combination of the `free` directions
+    Infinite {
+        particular: Vec<Rational>,
+        free: Vec<Vec<Rational>>,
+    },
+}
+
+// solves `matrix * x == rhs` by gauss-jordan elimination, where `matrix` has one row per equation
+pub fn solve(matrix: &[Vec<Rational>], rhs: &[Rational]) -> Solution {
+    assert_eq!(matrix.len(), rhs.len(), "one right-hand side per equation");
+    let vars = matrix.first().map_or(0, Vec::len);
+    let mut rows = matrix
+        .iter()
+        .zip(rhs)
+        .map(|(row, &rhs)| {
+            assert_eq!(
+                row.len(),
+                vars,
+                "every equation needs the same number of variables"
+            );
+            row.iter().copied().chain([rhs]).collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut pivots = vec![];
+    for var in 0..vars {
+        let rank = pivots.len();
+        let Some(pivot) = (rank..rows.len()).find(|&row| !rows[row][var].is_zero()) else {
+            continue;
+        };
+        rows.swap(rank, pivot);
+        let scale = rows[rank][var];
+        for cell in &mut rows[rank] {
+            *cell /= scale;
+        }
+        let pivot_row = rows[rank].clone();
+        for (row, cells) in rows.iter_mut().enumerate() {
+            let factor = cells[var];
+            if row != rank && !factor.is_zero() {
+                for (cell, &pivot) in cells.iter_mut().zip(&pivot_row).skip(var) {
+                    *cell -= factor * pivot;
+                }
+            }
+        }
+        pivots.push(var);
+    }
+
+    if rows[pivots.len()..].iter().any(|row| !row[vars].is_zero()) {
+        return Solution::None;
+    }
+
+    let mut particular = vec![Rational::ZERO; vars];
+    for (row, &var) in pivots.iter().enumerate() {
+        particular[var] = rows[row][vars];
+    }
+    if pivots.len() == vars {
+        return Solution::Unique(particular);
+    }
+
+    let free = (0..vars)
+        .filter(|var| !pivots.contains(var))
+        .map(|free_var| {
+            let mut direction = vec![Rational::ZERO; vars];
+            direction[free_var] = Rational::ONE;
+            for (row, &var) in pivots.iter().enumerate() {
+                direction[var] = -rows[row][free_var];
+            }
+            direction
+        })
+        .collect();
+    Solution::Infinite { particular, free }
+}
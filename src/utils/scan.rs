@@ -0,0 +1,152 @@
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+};
+
+// matches `input` against a format string where every `{}` is a typed capture:
+// `let (x, y): (i64, i64) = scan!("X={}, Y={}", line)?;`
+#[macro_export]
+macro_rules! scan {
+    ($format:literal, $input:expr) => {
+        $crate::utils::scan::scan($format, $input)
+    };
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanError(String);
+
+impl Display for ScanError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "scan failed: {}", self.0)
+    }
+}
+
+impl Error for ScanError {}
+
+pub trait Capture<'a>: Sized {
+    // parses a value from the start of `s`, given the literal text that must follow it
+    fn capture(s: &'a str, next: &str) -> Option<(Self, &'a str)>;
+}
+
+impl<'a> Capture<'a> for &'a str {
+    fn capture(s: &'a str, next: &str) -> Option<(Self, &'a str)> {
+        let end = if next.is_empty() {
+            s.len()
+        } else {
+            s.find(next)?
+        };
+        Some(s.split_at(end))
+    }
+}
+
+macro_rules! capture_impl {
+    ($($int:ty),*) => {$(
+        impl<'a> Capture<'a> for $int {
+            fn capture(s: &'a str, _: &str) -> Option<(Self, &'a str)> {
+                super::try_parse(s)
+            }
+        }
+    )*};
+}
+
+capture_impl!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+pub struct Scanner<'f, 'a> {
+    format: &'f str,
+    input: &'a str,
+    rest: &'a str,
+    captures: usize,
+}
+
+impl<'f, 'a> Scanner<'f, 'a> {
+    pub fn new(format: &'f str, input: &'a str) -> Self {
+        Self {
+            format,
+            input,
+            rest: input,
+            captures: 0,
+        }
+    }
+
+    fn literal(&mut self, literal: &str) -> Result<(), ScanError> {
+        self.rest = self.rest.strip_prefix(literal).ok_or_else(|| {
+            ScanError(format!(
+                "expected {literal:?} but found {:?} in {:?}",
+                self.rest, self.input
+            ))
+        })?;
+        Ok(())
+    }
+
+    pub fn capture<T: Capture<'a>>(&mut self) -> Result<T, ScanError> {
+        let Some((literal, format)) = self.format.split_once("{}") else {
+            return Err(ScanError(format!(
+                "format {:?} has only {} captures",
+                self.format, self.captures
+            )));
+        };
+        self.literal(literal)?;
+        self.format = format;
+        self.captures += 1;
+        let next = format.split_once("{}").map_or(format, |(next, _)| next);
+        let (value, rest) = T::capture(self.rest, next).ok_or_else(|| {
+            ScanError(format!(
+                "couldn't parse capture {} from {:?} in {:?}",
+                self.captures, self.rest, self.input
+            ))
+        })?;
+        self.rest = rest;
+        Ok(value)
+    }
+
+    pub fn finish(mut self) -> Result<(), ScanError> {
+        if self.format.contains("{}") {
+            return Err(ScanError(format!(
+                "format has captures left over after {}",
+                self.captures
+            )));
+        }
+        self.literal(self.format)?;
+        if self.rest.is_empty() {
+            Ok(())
+        } else {
+            Err(ScanError(format!(
+                "unexpected trailing {:?} in {:?}",
+                self.rest, self.input
+            )))
+        }
+    }
+}
+
+pub trait Scan<'a>: Sized {
+    fn scan(scanner: &mut Scanner<'_, 'a>) -> Result<Self, ScanError>;
+}
+
+impl<'a, T: Capture<'a>> Scan<'a> for T {
+    fn scan(scanner: &mut Scanner<'_, 'a>) -> Result<Self, ScanError> {
+        scanner.capture()
+    }
+}
+
+macro_rules! scan_impl {
+    ($($t:ident),*) => {
+        impl<'a, $($t: Capture<'a>),*> Scan<'a> for ($($t,)*) {
+            fn scan(scanner: &mut Scanner<'_, 'a>) -> Result<Self, ScanError> {
+                Ok(($(scanner.capture::<$t>()?,)*))
+            }
+        }
+    };
+}
+
+scan_impl!(A, B);
+scan_impl!(A, B, C);
+scan_impl!(A, B, C, D);
+scan_impl!(A, B, C, D, E);
+scan_impl!(A, B, C, D, E, F);
+
+pub fn scan<'a, T: Scan<'a>>(format: &str, input: &'a str) -> Result<T, ScanError> {
+    let mut scanner = Scanner::new(format, input);
+    let value = T::scan(&mut scanner)?;
+    scanner.finish()?;
+    Ok(value)
+}
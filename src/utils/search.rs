@@ -0,0 +1,299 @@
+use itertools::Itertools;
+use rustc_hash::{FxHashMap as HashMap, FxHashSet as HashSet};
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::{BinaryHeap, VecDeque},
+    hash::Hash,
+    iter,
+    ops::Add,
+};
+
+pub trait Cost: Copy + Ord + Default + Add<Output = Self> {}
+
+impl<C: Copy + Ord + Default + Add<Output = Self>> Cost for C {}
+
+#[derive(Clone, Debug)]
+pub struct Search<N, C> {
+    pub dist: HashMap<N, C>,
+    pub prev: HashMap<N, N>,
+    pub goal: Option<N>,
+}
+
+impl<N: Clone + Eq + Hash, C: Cost> Search<N, C> {
+    pub fn cost(&self) -> Option<C> {
+        self.goal.as_ref().map(|goal| self.dist[goal])
+    }
+
+    pub fn path(&self) -> Option<Vec<N>> {
+        self.goal.as_ref().and_then(|goal| self.path_to(goal))
+    }
+
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        self.dist.contains_key(node).then(|| {
+            let mut path = vec![node.clone()];
+            while let Some(prev) = self.prev.get(path.last().unwrap()) {
+                path.push(prev.clone());
+            }
+            path.reverse();
+            path
+        })
+    }
+}
+
+pub fn bfs<N, I>(
+    start: N,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Search<N, usize>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut search = Search {
+        dist: HashMap::from_iter([(start.clone(), 0)]),
+        prev: HashMap::default(),
+        goal: None,
+    };
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(node) = queue.pop_front() {
+        if is_goal(&node) {
+            search.goal = Some(node);
+            break;
+        }
+        let dist = search.dist[&node] + 1;
+        for neighbor in neighbors(&node) {
+            if !search.dist.contains_key(&neighbor) {
+                search.dist.insert(neighbor.clone(), dist);
+                search.prev.insert(neighbor.clone(), node.clone());
+                queue.push_back(neighbor);
+            }
+        }
+    }
+    search
+}
+
+pub fn dijkstra<N, C, I>(
+    start: N,
+    neighbors: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Search<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar(start, neighbors, |_| C::default(), is_goal)
+}
+
+struct Queued<N, C> {
+    node: N,
+    cost: C,
+    estimate: C,
+}
+
+impl<N, C: Ord> PartialEq for Queued<N, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<N, C: Ord> Eq for Queued<N, C> {}
+
+impl<N, C: Ord> PartialOrd for Queued<N, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, C: Ord> Ord for Queued<N, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        Reverse(&self.estimate).cmp(&Reverse(&other.estimate))
+    }
+}
+
+// `heuristic` must be consistent, never dropping by more than the cost of a step (and so never
+// overestimating the remaining cost), since settled nodes aren't reopened; otherwise the goal
+// may be reached suboptimally
+pub fn astar<N, C, I>(
+    start: N,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Search<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut search = Search {
+        dist: HashMap::default(),
+        prev: HashMap::default(),
+        goal: None,
+    };
+    let mut best = HashMap::from_iter([(start.clone(), (C::default(), None))]);
+    let mut queue = BinaryHeap::from([Queued {
+        estimate: heuristic(&start),
+        node: start,
+        cost: C::default(),
+    }]);
+
+    while let Some(Queued { node, cost, .. }) = queue.pop() {
+        if search.dist.contains_key(&node) || best[&node].0 < cost {
+            continue;
+        }
+        search.dist.insert(node.clone(), cost);
+        if let Some(prev) = best[&node].1.clone() {
+            search.prev.insert(node.clone(), prev);
+        }
+        if is_goal(&node) {
+            search.goal = Some(node);
+            break;
+        }
+        for (neighbor, step) in neighbors(&node) {
+            let cost = cost + step;
+            if search.dist.contains_key(&neighbor)
+                || best.get(&neighbor).is_some_and(|&(best, _)| best <= cost)
+            {
+                continue;
+            }
+            best.insert(neighbor.clone(), (cost, Some(node.clone())));
+            queue.push(Queued {
+                estimate: cost + heuristic(&neighbor),
+                node: neighbor,
+                cost,
+            });
+        }
+    }
+    search
+}
+
+#[derive(Clone, Debug)]
+pub struct ShortestPaths<N, C> {
+    pub start: N,
+    pub dist: HashMap<N, C>,
+    pub preds: HashMap<N, Vec<N>>,
+    pub goals: Vec<N>,
+}
+
+impl<N: Clone + Eq + Hash, C: Cost> ShortestPaths<N, C> {
+    pub fn cost(&self) -> Option<C> {
+        self.goals.first().map(|goal| self.dist[goal])
+    }
+
+    pub fn nodes(&self) -> HashSet<N> {
+        let mut nodes = HashSet::from_iter(self.goals.iter().cloned());
+        let mut stack = self.goals.clone();
+        while let Some(node) = stack.pop() {
+            for pred in self.preds.get(&node).into_iter().flatten() {
+                if nodes.insert(pred.clone()) {
+                    stack.push(pred.clone());
+                }
+            }
+        }
+        nodes
+    }
+
+    pub fn count_paths(&self) -> u64 {
+        let mut ways = HashMap::from_iter([(self.start.clone(), 1)]);
+        let nodes = self
+            .nodes()
+            .into_iter()
+            .sorted_by_key(|node| self.dist[node]);
+        for node in nodes {
+            let count = self
+                .preds
+                .get(&node)
+                .into_iter()
+                .flatten()
+                .map(|pred| ways[pred])
+                .sum::<u64>();
+            *ways.entry(node).or_default() += count;
+        }
+        self.goals.iter().map(|goal| ways[goal]).sum()
+    }
+
+    pub fn paths(&self) -> impl Iterator<Item = Vec<N>> + use<'_, N, C> {
+        let mut stack = self
+            .goals
+            .iter()
+            .map(|goal| vec![goal.clone()])
+            .collect_vec();
+        iter::from_fn(move || {
+            while let Some(mut path) = stack.pop() {
+                let last = path.last().unwrap();
+                if *last == self.start {
+                    path.reverse();
+                    return Some(path);
+                }
+                for pred in self.preds.get(last).into_iter().flatten() {
+                    let mut path = path.clone();
+                    path.push(pred.clone());
+                    stack.push(path);
+                }
+            }
+            None
+        })
+    }
+}
+
+// like `dijkstra`, but keeps every optimal predecessor and every goal reached at the optimal cost,
+// which only forms a DAG if all step costs are positive
+pub fn dijkstra_all<N, C, I>(
+    start: N,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> ShortestPaths<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut paths = ShortestPaths {
+        start: start.clone(),
+        dist: HashMap::from_iter([(start.clone(), C::default())]),
+        preds: HashMap::default(),
+        goals: vec![],
+    };
+    let mut settled = HashSet::default();
+    let mut queue = BinaryHeap::from([Queued {
+        node: start,
+        cost: C::default(),
+        estimate: C::default(),
+    }]);
+
+    while let Some(Queued { node, cost, .. }) = queue.pop() {
+        if paths.cost().is_some_and(|best| best < cost) {
+            break;
+        }
+        if paths.dist[&node] < cost || !settled.insert(node.clone()) {
+            continue;
+        }
+        if is_goal(&node) {
+            paths.goals.push(node);
+            continue;
+        }
+        for (neighbor, step) in neighbors(&node) {
+            let cost = cost + step;
+            if settled.contains(&neighbor) {
+                continue;
+            }
+            match paths.dist.get(&neighbor).map(|best| cost.cmp(best)) {
+                Some(Ordering::Greater) => {}
+                Some(Ordering::Equal) => {
+                    paths.preds.entry(neighbor).or_default().push(node.clone());
+                }
+                Some(Ordering::Less) | None => {
+                    paths.dist.insert(neighbor.clone(), cost);
+                    paths.preds.insert(neighbor.clone(), vec![node.clone()]);
+                    queue.push(Queued {
+                        node: neighbor,
+                        cost,
+                        estimate: cost,
+                    });
+                }
+            }
+        }
+    }
+    paths
+}